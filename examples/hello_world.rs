@@ -1,9 +1,9 @@
 // See https://github.com/banach-space/llvm-tutor/blob/main/HelloWorld/HelloWorld.cpp
 // for a more detailed explanation.
 
-use llvm_plugin::inkwell::values::FunctionValue;
 use llvm_plugin::{
-    FunctionAnalysisManager, LlvmFunctionPass, PassBuilder, PipelineParsing, PreservedAnalyses,
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
 };
 
 #[llvm_plugin::plugin(name = "HelloWorld", version = "0.1")]
@@ -22,7 +22,7 @@ struct HelloWorldPass;
 impl LlvmFunctionPass for HelloWorldPass {
     fn run_pass(
         &self,
-        function: &mut FunctionValue,
+        function: &mut FunctionRef,
         _manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses {
         eprintln!("(llvm-tutor) Hello from: {:?}", function.get_name());
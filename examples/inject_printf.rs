@@ -2,10 +2,10 @@
 // for a more detailed explanation.
 
 use llvm_plugin::inkwell::basic_block::BasicBlock;
-use llvm_plugin::inkwell::module::Module;
 use llvm_plugin::inkwell::AddressSpace;
 use llvm_plugin::{
-    LlvmModulePass, ModuleAnalysisManager, PassBuilder, PipelineParsing, PreservedAnalyses,
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
 };
 
 #[cfg(not(any(
@@ -49,7 +49,11 @@ fn plugin_registrar(builder: &mut PassBuilder) {
 
 struct InjectFuncCallPass;
 impl LlvmModulePass for InjectFuncCallPass {
-    fn run_pass(&self, module: &mut Module, _manager: &ModuleAnalysisManager) -> PreservedAnalyses {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
         let cx = module.get_context();
 
         let printf = match module.get_function("printf") {
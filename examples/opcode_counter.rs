@@ -3,10 +3,10 @@
 
 use std::collections::HashMap;
 
-use llvm_plugin::inkwell::values::{FunctionValue, InstructionOpcode};
+use llvm_plugin::inkwell::values::InstructionOpcode;
 use llvm_plugin::{
-    AnalysisKey, FunctionAnalysisManager, LlvmFunctionAnalysis, LlvmFunctionPass, PassBuilder,
-    PipelineParsing, PreservedAnalyses,
+    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis, LlvmFunctionPass,
+    PassBuilder, PipelineParsing, PreservedAnalyses,
 };
 
 #[llvm_plugin::plugin(name = "OpcodeCounter", version = "0.1")]
@@ -29,7 +29,7 @@ struct OpcodeCounterPrinterPass;
 impl LlvmFunctionPass for OpcodeCounterPrinterPass {
     fn run_pass(
         &self,
-        function: &mut FunctionValue,
+        function: &mut FunctionRef,
         manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses {
         let opcode_map = manager.get_result::<OpcodeCounterAnalysis>(function);
@@ -50,7 +50,7 @@ impl LlvmFunctionAnalysis for OpcodeCounterAnalysis {
 
     fn run_analysis(
         &self,
-        function: &FunctionValue,
+        function: &FunctionRef,
         _manager: &FunctionAnalysisManager,
     ) -> Self::Result {
         let mut opcode_map = HashMap::new();
@@ -5,10 +5,9 @@ use std::collections::HashMap;
 
 use either::Either;
 
-use llvm_plugin::inkwell::module::Module;
 use llvm_plugin::inkwell::values::{BasicValueEnum, InstructionOpcode};
 use llvm_plugin::{
-    AnalysisKey, LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, PassBuilder,
+    AnalysisKey, LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder,
     PipelineParsing, PreservedAnalyses,
 };
 
@@ -30,7 +29,11 @@ fn plugin_registrar(builder: &mut PassBuilder) {
 
 struct StaticCallCounterPrinterPass;
 impl LlvmModulePass for StaticCallCounterPrinterPass {
-    fn run_pass(&self, module: &mut Module, manager: &ModuleAnalysisManager) -> PreservedAnalyses {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
         let call_map = manager.get_result::<StaticCallCounterAnalysis>(module);
         print_static_counter_result(call_map);
         PreservedAnalyses::All
@@ -41,7 +44,7 @@ struct StaticCallCounterAnalysis;
 impl LlvmModuleAnalysis for StaticCallCounterAnalysis {
     type Result = HashMap<String, usize>;
 
-    fn run_analysis(&self, module: &Module, _manager: &ModuleAnalysisManager) -> Self::Result {
+    fn run_analysis(&self, module: &ModuleRef, _manager: &ModuleAnalysisManager) -> Self::Result {
         let mut call_map = HashMap::new();
 
         for func in module.get_functions() {
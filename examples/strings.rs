@@ -3,11 +3,12 @@
 
 use inkwell::values::{ArrayValue, AsValueRef};
 use llvm_plugin::inkwell::basic_block::BasicBlock;
-use llvm_plugin::inkwell::module::{Linkage, Module};
+use llvm_plugin::inkwell::module::Linkage;
 use llvm_plugin::inkwell::values::{BasicValueEnum, FunctionValue, GlobalValue};
 use llvm_plugin::inkwell::{AddressSpace, IntPredicate};
 use llvm_plugin::{
-    LlvmModulePass, ModuleAnalysisManager, PassBuilder, PipelineParsing, PreservedAnalyses,
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
 };
 
 #[cfg(not(any(
@@ -51,7 +52,11 @@ fn plugin_registrar(builder: &mut PassBuilder) {
 
 struct StringObfuscatorModPass;
 impl LlvmModulePass for StringObfuscatorModPass {
-    fn run_pass(&self, module: &mut Module, _manager: &ModuleAnalysisManager) -> PreservedAnalyses {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
         // transform the strings
         let global_strings = encode_global_strings(module);
 
@@ -80,7 +85,7 @@ enum GlobalString<'a> {
     Struct(GlobalValue<'a>, u32, u32),
 }
 
-fn encode_global_strings<'a>(module: &mut Module<'a>) -> Vec<GlobalString<'a>> {
+fn encode_global_strings<'a>(module: &ModuleRef<'a>) -> Vec<GlobalString<'a>> {
     let cx = module.get_context();
 
     module
@@ -140,7 +145,7 @@ pub fn array_as_const_string<'a>(arr: &'a ArrayValue) -> Option<&'a [u8]> {
     }
 }
 
-fn create_decode_fn<'a>(module: &mut Module<'a>) -> FunctionValue<'a> {
+fn create_decode_fn<'a>(module: &ModuleRef<'a>) -> FunctionValue<'a> {
     let cx = module.get_context();
 
     // create type `void decode(int8*, int32)`
@@ -271,7 +276,7 @@ fn create_decode_fn<'a>(module: &mut Module<'a>) -> FunctionValue<'a> {
 }
 
 fn create_decode_stub<'a>(
-    module: &mut Module<'a>,
+    module: &ModuleRef<'a>,
     global_strings: Vec<GlobalString<'a>>,
     decode_fn: FunctionValue<'a>,
 ) -> FunctionValue<'a> {
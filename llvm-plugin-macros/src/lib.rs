@@ -60,6 +60,9 @@ fn plugin_impl(attrs: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
     let registrar_name = &func.sig.ident;
     let registrar_name_sys = format_ident!("{}_sys", registrar_name);
 
+    let plugin_name = name.clone();
+    let registrar_str = registrar_name.to_string();
+
     let name = name + "\0";
     let version = version + "\0";
 
@@ -67,8 +70,14 @@ fn plugin_impl(attrs: TokenStream, input: TokenStream) -> syn::Result<TokenStrea
         #func
 
         extern "C" fn #registrar_name_sys(builder: *mut std::ffi::c_void) {
-            let mut builder = unsafe { llvm_plugin::PassBuilder::from_raw(builder) };
-            #registrar_name(&mut builder);
+            unsafe {
+                llvm_plugin::run_plugin_registrar__(
+                    #plugin_name,
+                    #registrar_str,
+                    builder,
+                    #registrar_name,
+                )
+            }
         }
 
         #[no_mangle]
@@ -2,10 +2,14 @@
 
 #include <memory>
 #include <mutex>
+#include <string>
 #include <type_traits>
 #include <utility>
 
 #include <llvm/IR/PassManager.h>
+#include <llvm/Support/TimeProfiler.h>
+
+#include "stack_trace.hh"
 
 namespace {
 
@@ -17,25 +21,52 @@ struct Analysis : public llvm::AnalysisInfoMixin<Analysis<IR>> {
 
   using ResultPtr = void *;
   using ResultDeleter = void (*)(ResultPtr);
-  using Result =
-      std::unique_ptr<std::remove_pointer_t<ResultPtr>, ResultDeleter>;
+
+  // Every Rust analysis shares this type, so the result is invalidated
+  // based on the key of its analysis rather than on `ID()`.
+  struct Result {
+    Result(ResultPtr Ptr, ResultDeleter Deleter, llvm::AnalysisKey *Key)
+        : Inner(Ptr, Deleter), Key(Key) {}
+
+    auto get() const -> ResultPtr { return this->Inner.get(); }
+
+    auto invalidate(typename IR::Unit & /*IrUnit*/,
+                    const llvm::PreservedAnalyses &PA,
+                    typename IR::AnalysisManager::Invalidator & /*Inv*/)
+        -> bool {
+      auto PAC = PA.getChecker(this->Key);
+      return !PAC.preserved() &&
+             !PAC.template preservedSet<
+                 llvm::AllAnalysesOn<typename IR::Unit>>();
+    }
+
+  private:
+    std::unique_ptr<std::remove_pointer_t<ResultPtr>, ResultDeleter> Inner;
+    llvm::AnalysisKey *Key;
+  };
 
   using Entrypoint = void (*)(DataPtr, typename IR::Unit &,
                               typename IR::AnalysisManager &, ResultPtr &,
                               ResultDeleter &);
 
-  Analysis(Entrypoint Func, Data AnalysisData)
-      : AnalysisData(std::move(AnalysisData)) {
+  Analysis(Entrypoint Func, Data AnalysisData, std::string Name,
+           llvm::AnalysisKey *Key)
+      : AnalysisData(std::move(AnalysisData)), Name(std::move(Name)) {
     this->Func = Func;
+    this->Key = Key;
   }
 
   auto run(typename IR::Unit &IrUnit, typename IR::AnalysisManager &AM)
       -> Result {
+    const auto StackTrace =
+        PrettyStackTraceRustPass<IR>("analysis", this->Name, IrUnit);
+    const auto TimeTrace = llvm::TimeTraceScope(
+        this->Name, [&] { return IrUnit.getName().str(); });
     auto *Ptr = (ResultPtr) nullptr;
     auto Deleter = (ResultDeleter) nullptr;
     this->Func(this->AnalysisData.get(), IrUnit, AM, Ptr, Deleter);
 
-    return {Ptr, Deleter};
+    return {Ptr, Deleter, this->Key};
   }
 
   static auto ID() // NOLINT(readability-identifier-naming)
@@ -49,8 +80,26 @@ struct Analysis : public llvm::AnalysisInfoMixin<Analysis<IR>> {
 private:
   Entrypoint Func;
   Data AnalysisData;
+  std::string Name;
+  llvm::AnalysisKey *Key;
 
   friend struct llvm::AnalysisInfoMixin<Analysis<IR>>;
 };
 
+template <typename IR>
+struct InvalidateAnalysis
+    : public llvm::PassInfoMixin<InvalidateAnalysis<IR>> {
+  explicit InvalidateAnalysis(llvm::AnalysisKey *Key) : Key(Key) {}
+
+  auto run(typename IR::Unit & /*IrUnit*/,
+           typename IR::AnalysisManager & /*AM*/) -> llvm::PreservedAnalyses {
+    auto PA = llvm::PreservedAnalyses::all();
+    PA.abandon(this->Key);
+    return PA;
+  }
+
+private:
+  llvm::AnalysisKey *Key;
+};
+
 } // namespace
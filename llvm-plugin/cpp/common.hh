@@ -7,9 +7,22 @@
 struct ModuleIR {
   using AnalysisManager = llvm::ModuleAnalysisManager;
   using Unit = llvm::Module;
+  static constexpr auto UnitKind = "module";
 };
 
 struct FunctionIR {
   using AnalysisManager = llvm::FunctionAnalysisManager;
   using Unit = llvm::Function;
+  static constexpr auto UnitKind = "function";
 };
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 19)
+#include <llvm/CodeGen/MachineFunction.h>
+#include <llvm/CodeGen/MachinePassManager.h>
+
+struct MachineFunctionIR {
+  using AnalysisManager = llvm::MachineFunctionAnalysisManager;
+  using Unit = llvm::MachineFunction;
+  static constexpr auto UnitKind = "machine function";
+};
+#endif
@@ -0,0 +1,134 @@
+#pragma once
+
+#include <string>
+#include <utility>
+
+#include <llvm/IR/DebugInfoMetadata.h>
+#include <llvm/IR/DebugLoc.h>
+#include <llvm/IR/DiagnosticInfo.h>
+#include <llvm/IR/DiagnosticPrinter.h>
+#include <llvm/ADT/SmallString.h>
+#include <llvm/IR/Function.h>
+#include <llvm/IR/InstIterator.h>
+#include <llvm/IR/Module.h>
+#include <llvm/Support/Path.h>
+
+namespace {
+enum class DiagnosticSeverity {
+  kError,
+  kWarning,
+  kRemark,
+  kNote,
+};
+
+auto getLlvmDiagnosticSeverity(DiagnosticSeverity Severity)
+    -> llvm::DiagnosticSeverity {
+  switch (Severity) {
+  case DiagnosticSeverity::kError:
+    return llvm::DS_Error;
+  case DiagnosticSeverity::kWarning:
+    return llvm::DS_Warning;
+  case DiagnosticSeverity::kRemark:
+    return llvm::DS_Remark;
+  case DiagnosticSeverity::kNote:
+    return llvm::DS_Note;
+  }
+  return llvm::DS_Error;
+}
+
+struct Diagnostic : public llvm::DiagnosticInfo {
+  Diagnostic(DiagnosticSeverity Severity, std::string Message,
+             std::string File = {}, unsigned Line = 0, unsigned Column = 0)
+      : llvm::DiagnosticInfo(getKind(), getLlvmDiagnosticSeverity(Severity)),
+        Message(std::move(Message)), File(std::move(File)), Line(Line),
+        Column(Column) {}
+
+  auto print(llvm::DiagnosticPrinter &DP) const -> void override {
+    if (!this->File.empty()) {
+      DP << this->File << ":" << this->Line << ":" << this->Column << ": ";
+    }
+    DP << this->Message;
+  }
+
+  static auto getKind() -> int {
+    static const auto Kind = llvm::getNextAvailablePluginDiagnosticKind();
+    return Kind;
+  }
+
+private:
+  std::string Message;
+  std::string File;
+  unsigned Line;
+  unsigned Column;
+};
+
+// Diagnostic attached to the debug location of an instruction. Unlike
+// `Diagnostic`, consumers can read its location with
+// `DiagnosticInfoWithLocationBase::getLocation`.
+struct LocatedDiagnostic : public llvm::DiagnosticInfoWithLocationBase {
+  LocatedDiagnostic(DiagnosticSeverity Severity, const llvm::Function &Fn,
+                    const llvm::DILocation &Loc, std::string Message)
+      : llvm::DiagnosticInfoWithLocationBase(
+            static_cast<llvm::DiagnosticKind>(Diagnostic::getKind()),
+            getLlvmDiagnosticSeverity(Severity), Fn, llvm::DebugLoc(&Loc)),
+        Message(std::move(Message)) {}
+
+  auto print(llvm::DiagnosticPrinter &DP) const -> void override {
+    const auto Loc = this->getLocation();
+    DP << Loc.getAbsolutePath() << ":" << Loc.getLine() << ":"
+       << Loc.getColumn() << ": " << this->Message;
+  }
+
+private:
+  std::string Message;
+};
+
+// Returns the path of the source file of `Loc`, joined to its compilation
+// directory the same way as `DiagnosticLocation::from_instruction`.
+auto getLocationPath(const llvm::DILocation &Loc) -> std::string {
+  const auto File = Loc.getFilename();
+  if (llvm::sys::path::is_absolute(File)) {
+    return File.str();
+  }
+
+  auto Path = llvm::SmallString<128>(Loc.getDirectory());
+  llvm::sys::path::append(Path, File);
+  return Path.str().str();
+}
+
+auto findDebugLoc(const llvm::Function &F, llvm::StringRef File,
+                  unsigned Line, unsigned Column)
+    -> const llvm::DILocation * {
+  for (const auto &I : llvm::instructions(F)) {
+    const auto *Loc = I.getDebugLoc().get();
+    if (Loc != nullptr && Loc->getLine() == Line &&
+        Loc->getColumn() == Column && getLocationPath(*Loc) == File) {
+      return Loc;
+    }
+  }
+  return nullptr;
+}
+
+// Finds the debug location of an instruction at the given source location,
+// in `F` or, if it is null, in the functions of `M`. `F` is set to the
+// function containing the instruction.
+auto findDebugLoc(llvm::Module *M, llvm::Function *&F, llvm::StringRef File,
+                  unsigned Line, unsigned Column)
+    -> const llvm::DILocation * {
+  if (F != nullptr) {
+    return findDebugLoc(*F, File, Line, Column);
+  }
+  if (M == nullptr) {
+    return nullptr;
+  }
+
+  for (auto &Fn : *M) {
+    if (const auto *Loc = findDebugLoc(Fn, File, Line, Column)) {
+      F = &Fn;
+      return Loc;
+    }
+  }
+  return nullptr;
+}
+
+} // namespace
@@ -4,15 +4,33 @@
 #include <utility>
 
 #include <llvm/ADT/ArrayRef.h>
+#include <llvm/Analysis/OptimizationRemarkEmitter.h>
+#include <llvm/IR/LLVMContext.h>
+#include <llvm/IR/DebugInfoMetadata.h>
 #include <llvm/IR/Function.h>
+#include <llvm/IR/Instruction.h>
 #include <llvm/IR/Module.h>
 #include <llvm/IR/PassManager.h>
 #include <llvm/Passes/PassBuilder.h>
 #include <llvm/Passes/PassPlugin.h>
+#include <llvm/Support/DebugCounter.h>
+#include <llvm/Support/ErrorHandling.h>
+#include <llvm/Support/TimeProfiler.h>
+#include <llvm/Support/raw_ostream.h>
 
 #include "analysis.hh"
 #include "common.hh"
+#include "diagnostic.hh"
+#include "inline_advisor.hh"
+#include "inline_order.hh"
+#include "instrumentation.hh"
+#include "loop_nest.hh"
+#include "machine_function.hh"
 #include "pass.hh"
+#include "pass_builder.hh"
+#include "remark.hh"
+#include "stack_trace.hh"
+#include "statistic.hh"
 
 #if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 14)
 #include <llvm/Passes/OptimizationLevel.h>
@@ -21,6 +39,18 @@ using LlvmOptLevel = llvm::OptimizationLevel;
 using LlvmOptLevel = llvm::PassBuilder::OptimizationLevel;
 #endif
 
+#ifdef _WIN32
+// opt.exe only exports functions to plugins, so the optimization levels
+// are defined here, with the same values as LLVM's. This keeps the public
+// `OptimizationLevel::Ox` constants usable on Windows.
+const LlvmOptLevel LlvmOptLevel::O0 = {/*SpeedLevel*/ 0, /*SizeLevel*/ 0};
+const LlvmOptLevel LlvmOptLevel::O1 = {/*SpeedLevel*/ 1, /*SizeLevel*/ 0};
+const LlvmOptLevel LlvmOptLevel::O2 = {/*SpeedLevel*/ 2, /*SizeLevel*/ 0};
+const LlvmOptLevel LlvmOptLevel::O3 = {/*SpeedLevel*/ 3, /*SizeLevel*/ 0};
+const LlvmOptLevel LlvmOptLevel::Os = {/*SpeedLevel*/ 2, /*SizeLevel*/ 1};
+const LlvmOptLevel LlvmOptLevel::Oz = {/*SpeedLevel*/ 2, /*SizeLevel*/ 2};
+#endif
+
 enum class OptimizationLevel { kO0, kO1, kO2, kO3, kOs, kOz };
 
 #if defined(LLVM_VERSION_MAJOR) &&                                             \
@@ -40,10 +70,6 @@ enum class ThinOrFullLTOPhaseFFI {
 
 namespace {
 auto getFFIOptimizationLevel(LlvmOptLevel Opt) -> OptimizationLevel {
-  // Starting from LLVM-11, llvm::OptimizationLevel::Ox is no longer
-  // an enum but a global static. Using these global statics on Windows
-  // would not compile, because an LLVM plugin links to opt.exe. The
-  // latter doesn't export such symbols.
   if (Opt.getSpeedupLevel() == 0 && Opt.getSizeLevel() == 0) {
     return OptimizationLevel::kO0;
   }
@@ -65,6 +91,31 @@ auto getFFIOptimizationLevel(LlvmOptLevel Opt) -> OptimizationLevel {
   return OptimizationLevel::kOz;
 }
 
+auto getLlvmOptimizationLevel(OptimizationLevel Opt) -> LlvmOptLevel {
+  switch (Opt) {
+  case OptimizationLevel::kO0:
+    return LlvmOptLevel::O0;
+  case OptimizationLevel::kO1:
+    return LlvmOptLevel::O1;
+  case OptimizationLevel::kO2:
+    return LlvmOptLevel::O2;
+  case OptimizationLevel::kO3:
+    return LlvmOptLevel::O3;
+  case OptimizationLevel::kOs:
+    return LlvmOptLevel::Os;
+  case OptimizationLevel::kOz:
+    return LlvmOptLevel::Oz;
+  }
+  return LlvmOptLevel::O2;
+}
+
+// The pipeline builders of LLVM assert on O0, whose pipelines are instead
+// built when parsing the textual ones (e.g. `default<O0>`).
+auto addO0Pipeline(llvm::ModulePassManager &PassManager,
+                   llvm::PassBuilder &Builder, llvm::StringRef Name) -> void {
+  llvm::cantFail(Builder.parsePassPipeline(PassManager, Name));
+}
+
 #ifdef LLVM_HAS_THIN_OR_FULL_LTO_PHASE
 inline auto getFFIThinOrFullLTOPhase(llvm::ThinOrFullLTOPhase Phase)
     -> ThinOrFullLTOPhaseFFI {
@@ -89,23 +140,29 @@ extern "C" {
 auto moduleAnalysisManagerRegisterPass(
     llvm::ModuleAnalysisManager &AM, Analysis<ModuleIR>::DataPtr AnalysisData,
     Analysis<ModuleIR>::DataDeleter Deleter,
-    Analysis<ModuleIR>::Entrypoint Entrypoint, llvm::AnalysisKey *Key) -> bool {
+    Analysis<ModuleIR>::Entrypoint Entrypoint, llvm::AnalysisKey *Key,
+    const char *NamePtr, std::uintptr_t NameLen) -> bool {
   const auto Lock = std::lock_guard{Analysis<ModuleIR>::MutexCurrentKey};
   Analysis<ModuleIR>::CurrentKey = Key;
-  return AM.registerPass(
-      [&] { return Analysis<ModuleIR>{Entrypoint, {AnalysisData, Deleter}}; });
+  return AM.registerPass([&] {
+    return Analysis<ModuleIR>{Entrypoint,
+                              {AnalysisData, Deleter},
+                              std::string(NamePtr, NameLen), Key};
+  });
 }
 
 auto functionAnalysisManagerRegisterPass(
     llvm::FunctionAnalysisManager &AM,
     Analysis<FunctionIR>::DataPtr AnalysisData,
     Analysis<FunctionIR>::DataDeleter Deleter,
-    Analysis<FunctionIR>::Entrypoint Entrypoint, llvm::AnalysisKey *Key)
-    -> bool {
+    Analysis<FunctionIR>::Entrypoint Entrypoint, llvm::AnalysisKey *Key,
+    const char *NamePtr, std::uintptr_t NameLen) -> bool {
   const auto Lock = std::lock_guard{Analysis<FunctionIR>::MutexCurrentKey};
   Analysis<FunctionIR>::CurrentKey = Key;
   return AM.registerPass([&] {
-    return Analysis<FunctionIR>{Entrypoint, {AnalysisData, Deleter}};
+    return Analysis<FunctionIR>{Entrypoint,
+                                {AnalysisData, Deleter},
+                                std::string(NamePtr, NameLen), Key};
   });
 }
 
@@ -370,20 +427,680 @@ auto passBuilderAddFunctionPipelineParsingCallback(
       });
 }
 
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 19)
+auto passBuilderAddMachineFunctionPipelineParsingCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    bool (*Callback)(const void *, const char *, std::uintptr_t,
+                     llvm::MachineFunctionPassManager &)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  Builder.registerPipelineParsingCallback(
+      [Data = std::move(Data), Callback](
+          llvm::StringRef PassName,
+          llvm::MachineFunctionPassManager &PassManager,
+          llvm::ArrayRef<llvm::PassBuilder::PipelineElement> /*unused*/) {
+        return Callback(Data.get(), PassName.data(), PassName.size(),
+                        PassManager);
+      });
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddLoopPipelineParsingCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    bool (*Callback)(const void *, const char *, std::uintptr_t,
+                     llvm::LoopPassManager &)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  Builder.registerPipelineParsingCallback(
+      [Data = std::move(Data), Callback](
+          llvm::StringRef PassName, llvm::LoopPassManager &PassManager,
+          llvm::ArrayRef<llvm::PassBuilder::PipelineElement> /*unused*/) {
+        return Callback(Data.get(), PassName.data(), PassName.size(),
+                        PassManager);
+      });
+}
+#endif
+
+auto passBuilderAddTopLevelPipelineParsingCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    bool (*Callback)(const void *, llvm::ModulePassManager &,
+                     const llvm::PassBuilder::PipelineElement *,
+                     std::uintptr_t)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 13)
+  Builder.registerParseTopLevelPipelineCallback(
+      [Data = std::move(Data), Callback](
+          llvm::ModulePassManager &PassManager,
+          llvm::ArrayRef<llvm::PassBuilder::PipelineElement> Pipeline) {
+        return Callback(Data.get(), PassManager, Pipeline.data(),
+                        Pipeline.size());
+      });
+#else
+  Builder.registerParseTopLevelPipelineCallback(
+      [Data = std::move(Data), Callback](
+          llvm::ModulePassManager &PassManager,
+          llvm::ArrayRef<llvm::PassBuilder::PipelineElement> Pipeline,
+          bool /*VerifyEachPass*/, bool /*DebugLogging*/) {
+        return Callback(Data.get(), PassManager, Pipeline.data(),
+                        Pipeline.size());
+      });
+#endif
+}
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 16)
+auto passBuilderSetInlineAdvisor(llvm::PassBuilder &Builder,
+                                 InlineAdvisorData **Slot, const void *DataPtr,
+                                 void (*Deleter)(const void *),
+                                 InlineAdvisorCallback Callback) -> void {
+  // The analyses are only registered the first time an advisor is set on
+  // this builder, the following times only replace the advisor.
+  if (*Slot == nullptr) {
+    auto Data = std::make_shared<InlineAdvisorData>();
+    *Slot = Data.get();
+
+    Builder.registerAnalysisRegistrationCallback(
+        [](llvm::ModuleAnalysisManager &AM) {
+          AM.registerPass([] {
+            return llvm::PluginInlineAdvisorAnalysis(&createInlineAdvisor);
+          });
+        });
+    Builder.registerAnalysisRegistrationCallback(
+        [Data = std::move(Data)](llvm::FunctionAnalysisManager &AM) {
+          AM.registerPass([&] { return InlineAdvisorDataAnalysis(Data); });
+        });
+  }
+
+  **Slot = {std::shared_ptr<const void>(DataPtr, Deleter), Callback};
+}
+
+auto inlineAdviceSetReason(std::string &Reason, const char *ReasonPtr,
+                           std::uintptr_t ReasonLen) -> void {
+  Reason.assign(ReasonPtr, ReasonLen);
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 17)
+auto passBuilderSetInlineOrder(llvm::PassBuilder &Builder,
+                               InlineOrderData **Slot, const void *DataPtr,
+                               void (*Deleter)(const void *),
+                               InlineOrderCallback Callback) -> void {
+  // Same as for inline advisors, the analyses are only registered the first
+  // time an inline order is set on this builder.
+  if (*Slot == nullptr) {
+    auto Data = std::make_shared<InlineOrderData>();
+    *Slot = Data.get();
+
+    Builder.registerAnalysisRegistrationCallback(
+        [Data = std::move(Data)](llvm::ModuleAnalysisManager &AM) {
+          AM.registerPass([] {
+            return llvm::PluginInlineOrderAnalysis(&createInlineOrder);
+          });
+          AM.registerPass([&] { return InlineOrderDataAnalysis(Data); });
+        });
+  }
+
+  **Slot = {std::shared_ptr<const void>(DataPtr, Deleter), Callback};
+}
+#endif
+
+auto pipelineElementGetName(const llvm::PassBuilder::PipelineElement &Element,
+                            std::uintptr_t *Len) -> const char * {
+  *Len = Element.Name.size();
+  return Element.Name.data();
+}
+
+auto pipelineElementGetInnerPipeline(
+    const llvm::PassBuilder::PipelineElement &Element, std::uintptr_t *Len)
+    -> const llvm::PassBuilder::PipelineElement * {
+  *Len = Element.InnerPipeline.size();
+  return Element.InnerPipeline.data();
+}
+
+auto pipelineElementAt(const llvm::PassBuilder::PipelineElement *Elements,
+                       std::uintptr_t Index)
+    -> const llvm::PassBuilder::PipelineElement * {
+  return &Elements[Index];
+}
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddShouldRunOptionalPassCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    bool (*Callback)(const void *, const char *, std::uintptr_t, IrUnitKind,
+                     const void *)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  auto *PIC = Builder.getPassInstrumentationCallbacks();
+  if (PIC == nullptr) {
+    return;
+  }
+
+  PIC->registerShouldRunOptionalPassCallback(
+      [Data = std::move(Data), Callback](llvm::StringRef PassID, llvm::Any IR) {
+        const void *Unit = nullptr;
+        const auto Kind = getFFIIrUnit(IR, &Unit);
+        return Callback(Data.get(), PassID.data(), PassID.size(), Kind, Unit);
+      });
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddBeforePassCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    void (*Callback)(const void *, const char *, std::uintptr_t, IrUnitKind,
+                     const void *)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  auto *PIC = Builder.getPassInstrumentationCallbacks();
+  if (PIC == nullptr) {
+    return;
+  }
+
+  PIC->registerBeforeNonSkippedPassCallback(
+      [Data = std::move(Data), Callback](llvm::StringRef PassID, llvm::Any IR) {
+        const void *Unit = nullptr;
+        const auto Kind = getFFIIrUnit(IR, &Unit);
+        Callback(Data.get(), PassID.data(), PassID.size(), Kind, Unit);
+      });
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddAfterPassCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    void (*Callback)(const void *, const char *, std::uintptr_t, IrUnitKind,
+                     const void *, PreservedAnalyses)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  auto *PIC = Builder.getPassInstrumentationCallbacks();
+  if (PIC == nullptr) {
+    return;
+  }
+
+  PIC->registerAfterPassCallback(
+      [Data = std::move(Data), Callback](llvm::StringRef PassID, llvm::Any IR,
+                                         const llvm::PreservedAnalyses &PA) {
+        const void *Unit = nullptr;
+        const auto Kind = getFFIIrUnit(IR, &Unit);
+        Callback(Data.get(), PassID.data(), PassID.size(), Kind, Unit,
+                 getFFIPreservedAnalyses(PA));
+      });
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddAfterPassInvalidatedCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    void (*Callback)(const void *, const char *, std::uintptr_t,
+                     PreservedAnalyses)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  auto *PIC = Builder.getPassInstrumentationCallbacks();
+  if (PIC == nullptr) {
+    return;
+  }
+
+  PIC->registerAfterPassInvalidatedCallback(
+      [Data = std::move(Data), Callback](llvm::StringRef PassID,
+                                         const llvm::PreservedAnalyses &PA) {
+        Callback(Data.get(), PassID.data(), PassID.size(),
+                 getFFIPreservedAnalyses(PA));
+      });
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddBeforeAnalysisCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    void (*Callback)(const void *, const char *, std::uintptr_t, IrUnitKind,
+                     const void *)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  auto *PIC = Builder.getPassInstrumentationCallbacks();
+  if (PIC == nullptr) {
+    return;
+  }
+
+  PIC->registerBeforeAnalysisCallback(
+      [Data = std::move(Data), Callback](llvm::StringRef PassID, llvm::Any IR) {
+        const void *Unit = nullptr;
+        const auto Kind = getFFIIrUnit(IR, &Unit);
+        Callback(Data.get(), PassID.data(), PassID.size(), Kind, Unit);
+      });
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddAfterAnalysisCallback(
+    llvm::PassBuilder &Builder, const void *DataPtr,
+    void (*Deleter)(const void *),
+    void (*Callback)(const void *, const char *, std::uintptr_t, IrUnitKind,
+                     const void *)) -> void {
+  const auto Data = std::shared_ptr<const void>(DataPtr, Deleter);
+
+  auto *PIC = Builder.getPassInstrumentationCallbacks();
+  if (PIC == nullptr) {
+    return;
+  }
+
+  PIC->registerAfterAnalysisCallback(
+      [Data = std::move(Data), Callback](llvm::StringRef PassID, llvm::Any IR) {
+        const void *Unit = nullptr;
+        const auto Kind = getFFIIrUnit(IR, &Unit);
+        Callback(Data.get(), PassID.data(), PassID.size(), Kind, Unit);
+      });
+}
+#endif
+
 auto modulePassManagerAddPass(llvm::ModulePassManager &PassManager,
                               Pass<ModuleIR>::DataPtr PassData,
                               Pass<ModuleIR>::DataDeleter Deleter,
-                              Pass<ModuleIR>::Entrypoint Entrypoint) -> void {
-  PassManager.addPass(Pass<ModuleIR>{Entrypoint, {PassData, Deleter}});
+                              Pass<ModuleIR>::Entrypoint Entrypoint,
+                              const char *NamePtr, std::uintptr_t NameLen,
+                              bool Required) -> void {
+  PassManager.addPass(Pass<ModuleIR>{Entrypoint,
+                                     {PassData, Deleter},
+                                     std::string(NamePtr, NameLen),
+                                     Required});
 }
 
 auto functionPassManagerAddPass(llvm::FunctionPassManager &PassManager,
                                 Pass<FunctionIR>::DataPtr PassData,
                                 Pass<FunctionIR>::DataDeleter Deleter,
-                                Pass<FunctionIR>::Entrypoint Entrypoint)
+                                Pass<FunctionIR>::Entrypoint Entrypoint,
+                                const char *NamePtr, std::uintptr_t NameLen,
+                                bool Required) -> void {
+  PassManager.addPass(Pass<FunctionIR>{Entrypoint,
+                                       {PassData, Deleter},
+                                       std::string(NamePtr, NameLen),
+                                       Required});
+}
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto loopPassManagerAddLoopNestPass(llvm::LoopPassManager &PassManager,
+                                    LoopNestPass::DataPtr PassData,
+                                    LoopNestPass::DataDeleter Deleter,
+                                    LoopNestPass::Entrypoint Entrypoint,
+                                    const char *NamePtr,
+                                    std::uintptr_t NameLen, bool Required)
+    -> void {
+  PassManager.addPass(LoopNestPass{Entrypoint,
+                                   {PassData, Deleter},
+                                   std::string(NamePtr, NameLen),
+                                   Required});
+}
+
+auto loopNestGetNumLoops(const llvm::LoopNest &LN) -> std::uintptr_t {
+  return LN.getNumLoops();
+}
+
+auto loopNestGetLoop(const llvm::LoopNest &LN, std::uintptr_t Index)
+    -> llvm::Loop * {
+  return LN.getLoop(Index);
+}
+
+auto loopNestGetNestDepth(const llvm::LoopNest &LN) -> unsigned {
+  return LN.getNestDepth();
+}
+
+auto loopNestGetMaxPerfectDepth(const llvm::LoopNest &LN) -> unsigned {
+  return LN.getMaxPerfectDepth();
+}
+
+auto loopNestGetFunction(const llvm::LoopNest &LN) -> llvm::Function * {
+  return LN.getOutermostLoop().getHeader()->getParent();
+}
+
+auto loopGetDepth(const llvm::Loop &L) -> unsigned { return L.getLoopDepth(); }
+
+auto loopGetHeader(const llvm::Loop &L) -> llvm::BasicBlock * {
+  return L.getHeader();
+}
+
+auto loopGetNumBlocks(const llvm::Loop &L) -> std::uintptr_t {
+  return L.getNumBlocks();
+}
+
+auto loopGetBlock(const llvm::Loop &L, std::uintptr_t Index)
+    -> llvm::BasicBlock * {
+  return L.getBlocks()[Index];
+}
+
+auto loopGetNumSubLoops(const llvm::Loop &L) -> std::uintptr_t {
+  return L.getSubLoops().size();
+}
+
+auto loopGetSubLoop(const llvm::Loop &L, std::uintptr_t Index)
+    -> llvm::Loop * {
+  return L.getSubLoops()[Index];
+}
+#endif
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 19)
+auto machineFunctionPassManagerAddPass(
+    llvm::MachineFunctionPassManager &PassManager,
+    Pass<MachineFunctionIR>::DataPtr PassData,
+    Pass<MachineFunctionIR>::DataDeleter Deleter,
+    Pass<MachineFunctionIR>::Entrypoint Entrypoint, const char *NamePtr,
+    std::uintptr_t NameLen, bool Required) -> void {
+  PassManager.addPass(Pass<MachineFunctionIR>{Entrypoint,
+                                              {PassData, Deleter},
+                                              std::string(NamePtr, NameLen),
+                                              Required});
+}
+
+auto machineFunctionGetContext(llvm::MachineFunction &MF)
+    -> llvm::LLVMContext * {
+  return &MF.getFunction().getContext();
+}
+
+// Value names are stored null-terminated, so they can be given as is.
+auto machineFunctionGetName(const llvm::MachineFunction &MF) -> const char * {
+  return MF.getName().data();
+}
+
+auto machineFunctionGetFunction(llvm::MachineFunction &MF)
+    -> llvm::Function * {
+  return &MF.getFunction();
+}
+
+auto machineFunctionGetFirstBlock(llvm::MachineFunction &MF)
+    -> llvm::MachineBasicBlock * {
+  return MF.empty() ? nullptr : &MF.front();
+}
+
+auto machineBasicBlockGetNext(llvm::MachineBasicBlock &MBB)
+    -> llvm::MachineBasicBlock * {
+  return getNextBlock(MBB);
+}
+
+auto machineBasicBlockGetNumber(const llvm::MachineBasicBlock &MBB) -> int {
+  return MBB.getNumber();
+}
+
+auto machineBasicBlockGetName(const llvm::MachineBasicBlock &MBB)
+    -> const char * {
+  return MBB.getName().data();
+}
+
+auto machineBasicBlockGetFirstInstr(llvm::MachineBasicBlock &MBB)
+    -> llvm::MachineInstr * {
+  return MBB.empty() ? nullptr : &*MBB.begin();
+}
+
+auto machineInstrGetNext(llvm::MachineInstr &MI) -> llvm::MachineInstr * {
+  return getNextInstr(MI);
+}
+
+auto machineInstrGetParent(llvm::MachineInstr &MI)
+    -> llvm::MachineBasicBlock * {
+  return MI.getParent();
+}
+
+auto machineInstrGetOpcode(const llvm::MachineInstr &MI) -> unsigned {
+  return MI.getOpcode();
+}
+
+auto machineInstrGetOpcodeName(const llvm::MachineInstr &MI,
+                               std::uintptr_t *Len) -> const char * {
+  const auto *TII = MI.getMF()->getSubtarget().getInstrInfo();
+  const auto Name = TII->getName(MI.getOpcode());
+  *Len = Name.size();
+  return Name.data();
+}
+
+auto machineInstrIsTerminator(const llvm::MachineInstr &MI) -> bool {
+  return MI.isTerminator();
+}
+
+auto machineInstrIsCall(const llvm::MachineInstr &MI) -> bool {
+  return MI.isCall();
+}
+
+auto machineInstrGetNumOperands(const llvm::MachineInstr &MI) -> unsigned {
+  return MI.getNumOperands();
+}
+
+auto machineInstrGetOperand(llvm::MachineInstr &MI, unsigned Index)
+    -> llvm::MachineOperand * {
+  return &MI.getOperand(Index);
+}
+
+auto machineOperandGetKind(const llvm::MachineOperand &MO)
+    -> MachineOperandKind {
+  return getMachineOperandKind(MO);
+}
+
+auto machineOperandGetReg(const llvm::MachineOperand &MO) -> unsigned {
+  return MO.getReg().id();
+}
+
+auto machineOperandIsDef(const llvm::MachineOperand &MO) -> bool {
+  return MO.isDef();
+}
+
+auto machineOperandGetImm(const llvm::MachineOperand &MO) -> std::int64_t {
+  return MO.getImm();
+}
+
+auto machineOperandGetBlock(const llvm::MachineOperand &MO)
+    -> llvm::MachineBasicBlock * {
+  return MO.getMBB();
+}
+#endif
+
+auto modulePassManagerAddFunctionPassManager(
+    llvm::ModulePassManager &PassManager, void *Data,
+    bool (*Callback)(void *, llvm::FunctionPassManager &)) -> void {
+  auto FunctionPassManager = llvm::FunctionPassManager();
+  if (!Callback(Data, FunctionPassManager)) {
+    return;
+  }
+  PassManager.addPass(
+      llvm::createModuleToFunctionPassAdaptor(std::move(FunctionPassManager)));
+}
+
+auto modulePassManagerAddModulePassManager(
+    llvm::ModulePassManager &PassManager, void *Data,
+    bool (*Callback)(void *, llvm::ModulePassManager &)) -> void {
+  auto ModulePassManager = llvm::ModulePassManager();
+  if (!Callback(Data, ModulePassManager)) {
+    return;
+  }
+  PassManager.addPass(std::move(ModulePassManager));
+}
+
+auto modulePassManagerAddPerModuleDefaultPipeline(
+    llvm::ModulePassManager &PassManager, llvm::PassBuilder &Builder,
+    OptimizationLevel Opt) -> void {
+  if (Opt == OptimizationLevel::kO0) {
+    addO0Pipeline(PassManager, Builder, "default<O0>");
+    return;
+  }
+  PassManager.addPass(
+      Builder.buildPerModuleDefaultPipeline(getLlvmOptimizationLevel(Opt)));
+}
+
+auto modulePassManagerAddModuleOptimizationPipeline(
+    llvm::ModulePassManager &PassManager, llvm::PassBuilder &Builder,
+    OptimizationLevel Opt) -> void {
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 16)
+  PassManager.addPass(Builder.buildModuleOptimizationPipeline(
+      getLlvmOptimizationLevel(Opt), llvm::ThinOrFullLTOPhase::None));
+#else
+  PassManager.addPass(
+      Builder.buildModuleOptimizationPipeline(getLlvmOptimizationLevel(Opt)));
+#endif
+}
+
+auto modulePassManagerAddThinLTOPreLinkDefaultPipeline(
+    llvm::ModulePassManager &PassManager, llvm::PassBuilder &Builder,
+    OptimizationLevel Opt) -> void {
+  if (Opt == OptimizationLevel::kO0) {
+    addO0Pipeline(PassManager, Builder, "thinlto-pre-link<O0>");
+    return;
+  }
+  PassManager.addPass(Builder.buildThinLTOPreLinkDefaultPipeline(
+      getLlvmOptimizationLevel(Opt)));
+}
+
+auto modulePassManagerAddLTOPreLinkDefaultPipeline(
+    llvm::ModulePassManager &PassManager, llvm::PassBuilder &Builder,
+    OptimizationLevel Opt) -> void {
+  if (Opt == OptimizationLevel::kO0) {
+    addO0Pipeline(PassManager, Builder, "lto-pre-link<O0>");
+    return;
+  }
+  PassManager.addPass(
+      Builder.buildLTOPreLinkDefaultPipeline(getLlvmOptimizationLevel(Opt)));
+}
+
+auto functionPassManagerAddFunctionSimplificationPipeline(
+    llvm::FunctionPassManager &PassManager, llvm::PassBuilder &Builder,
+    OptimizationLevel Opt) -> void {
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+  PassManager.addPass(Builder.buildFunctionSimplificationPipeline(
+      getLlvmOptimizationLevel(Opt), llvm::ThinOrFullLTOPhase::None));
+#else
+  PassManager.addPass(Builder.buildFunctionSimplificationPipeline(
+      getLlvmOptimizationLevel(Opt), llvm::PassBuilder::ThinLTOPhase::None));
+#endif
+}
+
+auto modulePassManagerAddInvalidateAnalysisPass(
+    llvm::ModulePassManager &PassManager, llvm::AnalysisKey *Key) -> void {
+  PassManager.addPass(InvalidateAnalysis<ModuleIR>{Key});
+}
+
+auto functionPassManagerAddInvalidateAnalysisPass(
+    llvm::FunctionPassManager &PassManager, llvm::AnalysisKey *Key) -> void {
+  PassManager.addPass(InvalidateAnalysis<FunctionIR>{Key});
+}
+
+auto modulePassManagerAddPipelineText(
+    llvm::ModulePassManager &PassManager, llvm::PassBuilder &Builder,
+    const char *TextPtr, std::uintptr_t TextLen, void *ErrorData,
+    void (*OnError)(void *, const char *, std::uintptr_t)) -> bool {
+  if (auto Err = Builder.parsePassPipeline(PassManager,
+                                           llvm::StringRef(TextPtr, TextLen))) {
+    const auto Msg = llvm::toString(std::move(Err));
+    OnError(ErrorData, Msg.data(), Msg.size());
+    return false;
+  }
+  return true;
+}
+
+auto functionPassManagerAddPipelineText(
+    llvm::FunctionPassManager &PassManager, llvm::PassBuilder &Builder,
+    const char *TextPtr, std::uintptr_t TextLen, void *ErrorData,
+    void (*OnError)(void *, const char *, std::uintptr_t)) -> bool {
+  if (auto Err = Builder.parsePassPipeline(PassManager,
+                                           llvm::StringRef(TextPtr, TextLen))) {
+    const auto Msg = llvm::toString(std::move(Err));
+    OnError(ErrorData, Msg.data(), Msg.size());
+    return false;
+  }
+  return true;
+}
+
+auto passBuilderGetTargetMachine(const llvm::PassBuilder &Builder)
+    -> llvm::TargetMachine * {
+  return getTargetMachine(Builder);
+}
+
+auto passBuilderGetPipelineTuningOptions(const llvm::PassBuilder &Builder)
+    -> PipelineTuningOptionsFFI {
+  const auto &PTO = getPipelineTuningOptions(Builder);
+  return {
+      PTO.LoopInterleaving,
+      PTO.LoopVectorization,
+      PTO.SLPVectorization,
+      PTO.LoopUnrolling,
+      PTO.CallGraphProfile,
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+      PTO.MergeFunctions,
+#else
+      false,
+#endif
+  };
+}
+
+auto passBuilderGetPGOOptions(const llvm::PassBuilder &Builder)
+    -> const llvm::PGOOptions * {
+  return getPGOOptions(Builder);
+}
+
+auto pgoOptionsGetAction(const llvm::PGOOptions &PGOOpt)
+    -> llvm::PGOOptions::PGOAction {
+  return PGOOpt.Action;
+}
+
+auto pgoOptionsGetCSAction(const llvm::PGOOptions &PGOOpt)
+    -> llvm::PGOOptions::CSPGOAction {
+  return PGOOpt.CSAction;
+}
+
+auto pgoOptionsGetProfileFile(const llvm::PGOOptions &PGOOpt,
+                              std::uintptr_t *Len) -> const char * {
+  *Len = PGOOpt.ProfileFile.size();
+  return PGOOpt.ProfileFile.data();
+}
+
+auto targetMachineGetTriple(const llvm::TargetMachine &TM,
+                            std::uintptr_t *Len) -> const char * {
+  const auto &Triple = TM.getTargetTriple().str();
+  *Len = Triple.size();
+  return Triple.data();
+}
+
+auto targetMachineGetCPU(const llvm::TargetMachine &TM, std::uintptr_t *Len)
+    -> const char * {
+  const auto CPU = TM.getTargetCPU();
+  *Len = CPU.size();
+  return CPU.data();
+}
+
+auto targetMachineGetFeatures(const llvm::TargetMachine &TM,
+                              std::uintptr_t *Len) -> const char * {
+  const auto Features = TM.getTargetFeatureString();
+  *Len = Features.size();
+  return Features.data();
+}
+
+auto targetMachineGetCodeModel(const llvm::TargetMachine &TM)
+    -> llvm::CodeModel::Model {
+  return TM.getCodeModel();
+}
+
+auto targetMachineGetDataLayout(
+    const llvm::TargetMachine &TM, void *Data,
+    void (*Write)(void *, const char *, std::uintptr_t)) -> void {
+  const auto Layout = TM.createDataLayout().getStringRepresentation();
+  Write(Data, Layout.data(), Layout.size());
+}
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+auto passBuilderAddClassToPassName(llvm::PassBuilder &Builder,
+                                   const char *NamePtr, std::uintptr_t NameLen)
     -> void {
-  PassManager.addPass(Pass<FunctionIR>{Entrypoint, {PassData, Deleter}});
+  auto *PIC = Builder.getPassInstrumentationCallbacks();
+  if (PIC == nullptr) {
+    return;
+  }
+
+  const auto Name = llvm::StringRef(NamePtr, NameLen);
+  PIC->addClassToPassName(Name, Name);
 }
+#endif
 
 #if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
 auto modulePassManagerIsEmpty(llvm::ModulePassManager &PassManager) -> bool {
@@ -447,5 +1164,152 @@ auto getFunctionAnalysisCachedResult(llvm::FunctionAnalysisManager &AM,
   return Result == nullptr ? nullptr : Result->get();
 }
 
+auto getOptimizationRemarkEmitter(llvm::FunctionAnalysisManager &AM,
+                                  llvm::Function &Function) -> void * {
+  auto &ORE = AM.getResult<llvm::OptimizationRemarkEmitterAnalysis>(Function);
+  return static_cast<void *>(&ORE);
+}
+
+auto optimizationRemarkEmitterIsEnabled(llvm::OptimizationRemarkEmitter &ORE)
+    -> bool {
+  return ORE.enabled();
+}
+
+auto optimizationRemarkEmitterEmit(
+    llvm::OptimizationRemarkEmitter &ORE, llvm::Function &Function,
+    RemarkKind Kind, const char *PassNamePtr, std::uintptr_t PassNameLen,
+    const char *RemarkNamePtr, std::uintptr_t RemarkNameLen,
+    const llvm::Instruction *Inst, const RemarkArg *Args,
+    std::uintptr_t ArgsLen) -> void {
+  // LLVM expects a null-terminated pass name.
+  const auto PassName = std::string(PassNamePtr, PassNameLen);
+  const auto RemarkName = llvm::StringRef(RemarkNamePtr, RemarkNameLen);
+
+  switch (Kind) {
+  case RemarkKind::kPassed:
+    emitRemark<llvm::OptimizationRemark>(ORE, Function, PassName, RemarkName,
+                                         Inst, Args, ArgsLen);
+    break;
+  case RemarkKind::kMissed:
+    emitRemark<llvm::OptimizationRemarkMissed>(
+        ORE, Function, PassName, RemarkName, Inst, Args, ArgsLen);
+    break;
+  case RemarkKind::kAnalysis:
+    emitRemark<llvm::OptimizationRemarkAnalysis>(
+        ORE, Function, PassName, RemarkName, Inst, Args, ArgsLen);
+    break;
+  }
+}
+
+auto moduleGetContext(llvm::Module &Module) -> llvm::LLVMContext * {
+  return &Module.getContext();
+}
+
+auto functionGetContext(llvm::Function &Function) -> llvm::LLVMContext * {
+  return &Function.getContext();
+}
+
+auto llvmContextDiagnose(llvm::LLVMContext &Context, llvm::Module *M,
+                         llvm::Function *F, DiagnosticSeverity Severity,
+                         const char *MsgPtr, std::uintptr_t MsgLen,
+                         const char *FilePtr, std::uintptr_t FileLen,
+                         std::uint32_t Line, std::uint32_t Column) -> void {
+  auto Message = std::string(MsgPtr, MsgLen);
+  auto File =
+      FilePtr == nullptr ? std::string() : std::string(FilePtr, FileLen);
+
+  // Only the source location is kept on the Rust side. When it is the debug
+  // location of an instruction of the IR unit, the diagnostic is attached to
+  // this debug location.
+  if (!File.empty()) {
+    if (const auto *Loc = findDebugLoc(M, F, File, Line, Column)) {
+      Context.diagnose(
+          LocatedDiagnostic{Severity, *F, *Loc, std::move(Message)});
+      return;
+    }
+  }
+
+  Context.diagnose(
+      Diagnostic{Severity, std::move(Message), std::move(File), Line, Column});
+}
+
+auto instructionGetDebugLoc(llvm::Instruction &Inst, const char **DirPtr,
+                            std::uintptr_t *DirLen, const char **FilePtr,
+                            std::uintptr_t *FileLen, std::uint32_t *Line,
+                            std::uint32_t *Column) -> bool {
+  const auto *Loc = Inst.getDebugLoc().get();
+  if (Loc == nullptr) {
+    return false;
+  }
+
+  const auto Dir = Loc->getDirectory();
+  const auto File = Loc->getFilename();
+  *DirPtr = Dir.data();
+  *DirLen = Dir.size();
+  *FilePtr = File.data();
+  *FileLen = File.size();
+  *Line = Loc->getLine();
+  *Column = Loc->getColumn();
+  return true;
+}
+
+auto llvmErrsWrite(const char *Ptr, std::uintptr_t Len) -> void {
+  llvm::errs() << llvm::StringRef(Ptr, Len);
+}
+
+[[noreturn]] auto reportFatalError(const char *MsgPtr, std::uintptr_t MsgLen)
+    -> void {
+  llvm::report_fatal_error(llvm::StringRef(MsgPtr, MsgLen), false);
+}
+
+auto debugCounterRegister(const char *NamePtr, std::uintptr_t NameLen,
+                          const char *DescPtr, std::uintptr_t DescLen)
+    -> std::uint32_t {
+  return llvm::DebugCounter::registerCounter(
+      llvm::StringRef(NamePtr, NameLen), llvm::StringRef(DescPtr, DescLen));
+}
+
+auto debugCounterShouldExecute(std::uint32_t Id) -> bool {
+  return llvm::DebugCounter::shouldExecute(Id);
+}
+
+auto statisticNew(const char *DebugTypePtr, std::uintptr_t DebugTypeLen,
+                  const char *NamePtr, std::uintptr_t NameLen,
+                  const char *DescPtr, std::uintptr_t DescLen) -> Statistic * {
+  // never freed, since LLVM prints the statistics at exit
+  return new Statistic(std::string(DebugTypePtr, DebugTypeLen),
+                       std::string(NamePtr, NameLen),
+                       std::string(DescPtr, DescLen));
+}
+
+auto statisticAdd(Statistic &Stat, std::uint32_t Value) -> void {
+  Stat.Inner += Value;
+}
+
+auto statisticUpdateMax(Statistic &Stat, std::uint32_t Value) -> void {
+  Stat.Inner.updateMax(Value);
+}
+
+auto statisticGetValue(const Statistic &Stat) -> std::uint32_t {
+  return Stat.Inner.getValue();
+}
+
+auto timeTraceProfilerIsEnabled() -> bool {
+  return llvm::timeTraceProfilerEnabled();
+}
+
+auto timeTraceProfilerBegin(const char *NamePtr, std::uintptr_t NameLen,
+                            const char *DetailPtr, std::uintptr_t DetailLen)
+    -> void {
+  llvm::timeTraceProfilerBegin(llvm::StringRef(NamePtr, NameLen),
+                               llvm::StringRef(DetailPtr, DetailLen));
+}
+
+auto timeTraceProfilerEnd() -> void { llvm::timeTraceProfilerEnd(); }
+
+auto setPluginName(const char *NamePtr, std::uintptr_t NameLen) -> void {
+  getPluginName() = std::string(NamePtr, NameLen);
+}
+
 auto llvmPluginApiVersion() -> std::uint32_t { return LLVM_PLUGIN_API_VERSION; }
 }
@@ -0,0 +1,214 @@
+#pragma once
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 16)
+#include <cstdint>
+#include <memory>
+#include <string>
+#include <utility>
+
+#include <llvm/ADT/StringRef.h>
+#include <llvm/Analysis/AssumptionCache.h>
+#include <llvm/Analysis/BlockFrequencyInfo.h>
+#include <llvm/Analysis/InlineAdvisor.h>
+#include <llvm/Analysis/InlineCost.h>
+#include <llvm/Analysis/OptimizationRemarkEmitter.h>
+#include <llvm/Analysis/ProfileSummaryInfo.h>
+#include <llvm/Analysis/TargetLibraryInfo.h>
+#include <llvm/Analysis/TargetTransformInfo.h>
+#include <llvm/IR/DiagnosticInfo.h>
+#include <llvm/IR/Function.h>
+#include <llvm/IR/InstrTypes.h>
+#include <llvm/IR/Module.h>
+#include <llvm/IR/PassManager.h>
+
+namespace {
+enum class InlineCostKind {
+  kVariable,
+  kAlways,
+  kNever,
+};
+
+struct InlineCostFFI {
+  InlineCostKind Kind;
+  std::int32_t Cost;
+  std::int32_t Threshold;
+};
+
+using InlineAdvisorCallback = bool (*)(const void *, llvm::Function &,
+                                       llvm::Function &, llvm::CallBase &,
+                                       const InlineCostFFI &, std::string &);
+
+// Analysis giving access to some data set on the pass builder which
+// registered it. Some factories given to LLVM (e.g. the inline advisor one)
+// are plain function pointers, and therefore get the data of the plugin
+// from such an analysis. Its result is never invalidated.
+template <typename IRUnitT, typename DataT>
+class PassBuilderDataAnalysis
+    : public llvm::AnalysisInfoMixin<PassBuilderDataAnalysis<IRUnitT, DataT>> {
+public:
+  struct Result {
+    std::shared_ptr<const DataT> Data;
+
+    auto invalidate(IRUnitT &, const llvm::PreservedAnalyses &,
+                    typename llvm::AnalysisManager<IRUnitT>::Invalidator &)
+        -> bool {
+      return false;
+    }
+  };
+
+  explicit PassBuilderDataAnalysis(std::shared_ptr<const DataT> Data)
+      : Data(std::move(Data)) {}
+
+  auto run(IRUnitT &, llvm::AnalysisManager<IRUnitT> &) -> Result {
+    return {this->Data};
+  }
+
+  static inline llvm::AnalysisKey Key;
+
+private:
+  std::shared_ptr<const DataT> Data;
+};
+
+struct InlineAdvisorData {
+  std::shared_ptr<const void> Data;
+  InlineAdvisorCallback Callback = nullptr;
+};
+
+using InlineAdvisorDataAnalysis =
+    PassBuilderDataAnalysis<llvm::Function, InlineAdvisorData>;
+
+auto getFFIInlineCost(const llvm::InlineCost &IC) -> InlineCostFFI {
+  if (IC.isAlways()) {
+    return {InlineCostKind::kAlways, 0, 0};
+  }
+  if (IC.isNever()) {
+    return {InlineCostKind::kNever, 0, 0};
+  }
+  return {InlineCostKind::kVariable, IC.getCost(), IC.getThreshold()};
+}
+
+// Same cost as the one computed by the default inline advisor of LLVM.
+auto getDefaultInlineCost(llvm::CallBase &CB, llvm::Function &Callee,
+                          llvm::FunctionAnalysisManager &FAM,
+                          const llvm::InlineParams &Params) -> InlineCostFFI {
+  auto &Caller = *CB.getCaller();
+  auto *PSI = FAM.getResult<llvm::ModuleAnalysisManagerFunctionProxy>(Caller)
+                  .getCachedResult<llvm::ProfileSummaryAnalysis>(
+                      *Caller.getParent());
+
+  auto GetAssumptionCache = [&](llvm::Function &F) -> llvm::AssumptionCache & {
+    return FAM.getResult<llvm::AssumptionAnalysis>(F);
+  };
+  auto GetBFI = [&](llvm::Function &F) -> llvm::BlockFrequencyInfo & {
+    return FAM.getResult<llvm::BlockFrequencyAnalysis>(F);
+  };
+  auto GetTLI = [&](llvm::Function &F) -> const llvm::TargetLibraryInfo & {
+    return FAM.getResult<llvm::TargetLibraryAnalysis>(F);
+  };
+
+  auto &CalleeTTI = FAM.getResult<llvm::TargetIRAnalysis>(Callee);
+  const auto IC = llvm::getInlineCost(CB, Params, CalleeTTI,
+                                      GetAssumptionCache, GetTLI, GetBFI, PSI);
+  return getFFIInlineCost(IC);
+}
+
+class RustInlineAdvice : public llvm::InlineAdvice {
+public:
+  RustInlineAdvice(llvm::InlineAdvisor *Advisor, llvm::CallBase &CB,
+                   llvm::OptimizationRemarkEmitter &ORE,
+                   bool IsInliningRecommended, std::string Reason)
+      : InlineAdvice(Advisor, CB, ORE, IsInliningRecommended),
+        Reason(std::move(Reason)) {}
+
+private:
+  auto recordInliningImpl() -> void override {
+    this->emitRemark<llvm::OptimizationRemark>("Inlined", " inlined into ",
+                                               this->Reason);
+  }
+
+  auto recordInliningWithCalleeDeletedImpl() -> void override {
+    this->emitRemark<llvm::OptimizationRemark>("Inlined", " inlined into ",
+                                               this->Reason);
+  }
+
+  auto recordUnsuccessfulInliningImpl(const llvm::InlineResult &Result)
+      -> void override {
+    this->emitRemark<llvm::OptimizationRemarkMissed>(
+        "NotInlined", " will not be inlined into ",
+        Result.getFailureReason());
+  }
+
+  auto recordUnattemptedInliningImpl() -> void override {
+    if (!this->isInliningRecommended()) {
+      this->emitRemark<llvm::OptimizationRemarkMissed>(
+          "NotInlined", " will not be inlined into ", this->Reason);
+    }
+  }
+
+  template <typename RemarkT>
+  auto emitRemark(llvm::StringRef RemarkName, llvm::StringRef Message,
+                  llvm::StringRef Why) -> void {
+    this->ORE.emit([&] {
+      RemarkT Remark("inline", RemarkName, this->DLoc, this->Block);
+      Remark << llvm::ore::NV("Callee", this->Callee) << Message
+             << llvm::ore::NV("Caller", this->Caller);
+      if (!Why.empty()) {
+        Remark << ": " << llvm::ore::NV("Reason", Why);
+      }
+      return Remark;
+    });
+  }
+
+  std::string Reason;
+};
+
+class RustInlineAdvisor : public llvm::InlineAdvisor {
+public:
+  RustInlineAdvisor(llvm::Module &M, llvm::FunctionAnalysisManager &FAM,
+                    llvm::InlineParams Params, llvm::InlineContext IC)
+      : InlineAdvisor(M, FAM, IC), Params(std::move(Params)) {}
+
+private:
+  auto getAdviceImpl(llvm::CallBase &CB)
+      -> std::unique_ptr<llvm::InlineAdvice> override {
+    auto &ORE = this->getCallerORE(CB);
+    auto *Callee = CB.getCalledFunction();
+    if (Callee == nullptr) {
+      return std::make_unique<RustInlineAdvice>(this, CB, ORE, false,
+                                                "indirect call");
+    }
+
+    // Same as the ML inline advisor of LLVM, mandatory decisions (e.g.
+    // `noinline` or `alwaysinline` callees, recursive calls) are taken
+    // without consulting the Rust advisor.
+    const auto Kind = getMandatoryKind(CB, this->FAM, ORE);
+    if (Kind == MandatoryInliningKind::Never || CB.getCaller() == Callee) {
+      return this->getMandatoryAdvice(CB, false);
+    }
+    if (Kind == MandatoryInliningKind::Always) {
+      return this->getMandatoryAdvice(CB, true);
+    }
+
+    const auto Cost =
+        getDefaultInlineCost(CB, *Callee, this->FAM, this->Params);
+
+    auto Reason = std::string();
+    const auto &Data =
+        *this->FAM.getResult<InlineAdvisorDataAnalysis>(*CB.getCaller()).Data;
+    const auto Inline = Data.Callback(Data.Data.get(), *CB.getCaller(),
+                                      *Callee, CB, Cost, Reason);
+
+    return std::make_unique<RustInlineAdvice>(this, CB, ORE, Inline,
+                                              std::move(Reason));
+  }
+
+  llvm::InlineParams Params;
+};
+
+auto createInlineAdvisor(llvm::Module &M, llvm::FunctionAnalysisManager &FAM,
+                         llvm::InlineParams Params, llvm::InlineContext IC)
+    -> llvm::InlineAdvisor * {
+  return new RustInlineAdvisor(M, FAM, std::move(Params), IC);
+}
+} // namespace
+#endif
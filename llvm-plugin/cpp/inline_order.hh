@@ -0,0 +1,116 @@
+#pragma once
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 17)
+#include <algorithm>
+#include <cassert>
+#include <cstdint>
+#include <memory>
+#include <utility>
+#include <vector>
+
+#include <llvm/ADT/STLExtras.h>
+#include <llvm/ADT/STLFunctionalExtras.h>
+#include <llvm/Analysis/InlineCost.h>
+#include <llvm/Analysis/InlineOrder.h>
+#include <llvm/IR/Function.h>
+#include <llvm/IR/InstrTypes.h>
+#include <llvm/IR/Module.h>
+
+#include "inline_advisor.hh"
+
+namespace {
+using InlineOrderCallback = std::int64_t (*)(const void *, llvm::Function &,
+                                             llvm::Function &,
+                                             llvm::CallBase &,
+                                             const InlineCostFFI &);
+
+struct InlineOrderData {
+  std::shared_ptr<const void> Data;
+  InlineOrderCallback Callback = nullptr;
+};
+
+using InlineOrderDataAnalysis =
+    PassBuilderDataAnalysis<llvm::Module, InlineOrderData>;
+
+class RustInlineOrder
+    : public llvm::InlineOrder<std::pair<llvm::CallBase *, int>> {
+  using Element = std::pair<llvm::CallBase *, int>;
+
+  struct Entry {
+    Element Elt;
+    std::int64_t Priority;
+  };
+
+public:
+  RustInlineOrder(llvm::FunctionAnalysisManager &FAM,
+                  const llvm::InlineParams &Params,
+                  std::shared_ptr<const InlineOrderData> Data)
+      : FAM(FAM), Params(Params), Data(std::move(Data)) {}
+
+  auto size() -> size_t override { return this->Heap.size(); }
+
+  auto push(const Element &Elt) -> void override {
+    this->Heap.push_back({Elt, this->getPriority(*Elt.first)});
+    std::push_heap(this->Heap.begin(), this->Heap.end(), isLess);
+  }
+
+  auto pop() -> Element override {
+    assert(this->size() > 0);
+
+    // The priority of a call site may have decreased since it was pushed,
+    // e.g. because of other call sites inlined into its caller.
+    while (this->updateAndCheckDecreased(this->Heap.front())) {
+      std::pop_heap(this->Heap.begin(), this->Heap.end(), isLess);
+      std::push_heap(this->Heap.begin(), this->Heap.end(), isLess);
+    }
+
+    std::pop_heap(this->Heap.begin(), this->Heap.end(), isLess);
+    const auto Elt = this->Heap.back().Elt;
+    this->Heap.pop_back();
+    return Elt;
+  }
+
+  auto erase_if(llvm::function_ref<bool(Element)> Pred) -> void override {
+    llvm::erase_if(this->Heap, [&](const Entry &E) { return Pred(E.Elt); });
+    std::make_heap(this->Heap.begin(), this->Heap.end(), isLess);
+  }
+
+private:
+  static auto isLess(const Entry &L, const Entry &R) -> bool {
+    return L.Priority < R.Priority;
+  }
+
+  auto updateAndCheckDecreased(Entry &E) -> bool {
+    const auto OldPriority = E.Priority;
+    E.Priority = this->getPriority(*E.Elt.first);
+    return E.Priority < OldPriority;
+  }
+
+  auto getPriority(llvm::CallBase &CB) -> std::int64_t {
+    auto *Callee = CB.getCalledFunction();
+    if (Callee == nullptr) {
+      return INT64_MIN;
+    }
+
+    const auto Cost =
+        getDefaultInlineCost(CB, *Callee, this->FAM, this->Params);
+
+    return this->Data->Callback(this->Data->Data.get(), *CB.getCaller(),
+                                *Callee, CB, Cost);
+  }
+
+  llvm::FunctionAnalysisManager &FAM;
+  llvm::InlineParams Params;
+  std::shared_ptr<const InlineOrderData> Data;
+  std::vector<Entry> Heap;
+};
+
+auto createInlineOrder(llvm::FunctionAnalysisManager &FAM,
+                       const llvm::InlineParams &Params,
+                       llvm::ModuleAnalysisManager &MAM, llvm::Module &M)
+    -> std::unique_ptr<llvm::InlineOrder<std::pair<llvm::CallBase *, int>>> {
+  auto Data = MAM.getResult<InlineOrderDataAnalysis>(M).Data;
+  return std::make_unique<RustInlineOrder>(FAM, Params, std::move(Data));
+}
+} // namespace
+#endif
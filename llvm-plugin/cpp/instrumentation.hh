@@ -0,0 +1,50 @@
+#pragma once
+
+#include <llvm/ADT/Any.h>
+#include <llvm/Analysis/LazyCallGraph.h>
+#include <llvm/Analysis/LoopInfo.h>
+#include <llvm/IR/Function.h>
+#include <llvm/IR/Module.h>
+#include <llvm/IR/PassManager.h>
+
+#include "pass.hh"
+
+namespace {
+enum class IrUnitKind {
+  kModule,
+  kFunction,
+  kLoop,
+  kCallGraphScc,
+  kOther,
+};
+
+// Loops are given along with their parent function, since the loop API is
+// not available to plugins.
+inline auto getFFIIrUnit(const llvm::Any &IR, const void **Unit)
+    -> IrUnitKind {
+  if (const auto *M = llvm::any_cast<const llvm::Module *>(&IR)) {
+    *Unit = *M;
+    return IrUnitKind::kModule;
+  }
+  if (const auto *F = llvm::any_cast<const llvm::Function *>(&IR)) {
+    *Unit = *F;
+    return IrUnitKind::kFunction;
+  }
+  if (const auto *L = llvm::any_cast<const llvm::Loop *>(&IR)) {
+    *Unit = (*L)->getHeader()->getParent();
+    return IrUnitKind::kLoop;
+  }
+  *Unit = nullptr;
+  if (llvm::any_cast<const llvm::LazyCallGraph::SCC *>(&IR) != nullptr) {
+    return IrUnitKind::kCallGraphScc;
+  }
+  return IrUnitKind::kOther;
+}
+
+inline auto getFFIPreservedAnalyses(const llvm::PreservedAnalyses &PA)
+    -> PreservedAnalyses {
+  return PA.areAllPreserved() ? PreservedAnalyses::kAll
+                              : PreservedAnalyses::kNone;
+}
+
+} // namespace
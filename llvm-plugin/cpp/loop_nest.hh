@@ -0,0 +1,93 @@
+#pragma once
+
+#include <memory>
+#include <string>
+#include <type_traits>
+#include <utility>
+
+#include <llvm/ADT/StringRef.h>
+#include <llvm/Analysis/LoopNestAnalysis.h>
+#include <llvm/IR/PassManager.h>
+#include <llvm/Support/TimeProfiler.h>
+#include <llvm/Support/raw_ostream.h>
+#include <llvm/Transforms/Scalar/LoopPassManager.h>
+
+#include "pass.hh"
+#include "stack_trace.hh"
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 12)
+namespace {
+struct LoopNestIR {
+  using Unit = llvm::LoopNest;
+  static constexpr auto UnitKind = "loop nest";
+};
+
+// Loop nest passes are run with extra arguments, so they cannot reuse
+// `Pass<IR>`.
+struct LoopNestPass : public llvm::PassInfoMixin<LoopNestPass> {
+  using DataPtr = const void *;
+  using DataDeleter = void (*)(DataPtr);
+  using Data = std::unique_ptr<std::remove_pointer_t<DataPtr>, DataDeleter>;
+
+  using Entrypoint = PreservedAnalyses (*)(DataPtr, llvm::LoopNest &);
+
+  LoopNestPass(Entrypoint Func, Data PassData, std::string Name,
+               bool Required)
+      : PassData(std::move(PassData)), Name(std::move(Name)) {
+    this->Func = Func;
+    this->Required = Required;
+  }
+
+  auto run(llvm::LoopNest &LN, llvm::LoopAnalysisManager & /*unused*/,
+           llvm::LoopStandardAnalysisResults & /*unused*/,
+           llvm::LPMUpdater & /*unused*/) -> llvm::PreservedAnalyses {
+    const auto StackTrace =
+        PrettyStackTraceRustPass<LoopNestIR>("pass", this->Name, LN);
+    const auto TimeTrace = llvm::TimeTraceScope(
+        this->Name, [&] { return LN.getName().str(); });
+    return (this->Func(this->PassData.get(), LN) == PreservedAnalyses::kAll
+                ? llvm::PreservedAnalyses::all()
+                : llvm::getLoopPassPreservedAnalyses());
+  }
+
+  auto getName() const -> llvm::StringRef { return this->Name; }
+
+  auto getRequired() const -> bool { return this->Required; }
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 14)
+  // Same as `Pass<IR>::printPipeline`.
+  auto printPipeline(llvm::raw_ostream &OS,
+                     llvm::function_ref<llvm::StringRef(llvm::StringRef)>
+                         MapClassName2PassName) -> void {
+    OS << MapClassName2PassName(this->Name);
+  }
+#endif
+
+private:
+  Entrypoint Func;
+  Data PassData;
+  std::string Name;
+  bool Required;
+};
+
+using LoopNestPassModel =
+    llvm::detail::PassModel<llvm::LoopNest, LoopNestPass,
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR < 18)
+                            llvm::PreservedAnalyses,
+#endif
+                            llvm::LoopAnalysisManager,
+                            llvm::LoopStandardAnalysisResults &,
+                            llvm::LPMUpdater &>;
+} // namespace
+
+// Same as for `Pass<IR>`, there is no hook for these two members.
+namespace llvm::detail {
+template <> auto LoopNestPassModel::name() const -> llvm::StringRef {
+  return this->Pass.getName();
+}
+
+template <> auto LoopNestPassModel::isRequired() const -> bool {
+  return this->Pass.getRequired();
+}
+} // namespace llvm::detail
+#endif
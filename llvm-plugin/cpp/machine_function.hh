@@ -0,0 +1,49 @@
+#pragma once
+
+#include <llvm/Config/llvm-config.h>
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 19)
+#include <iterator>
+
+#include <llvm/CodeGen/MachineBasicBlock.h>
+#include <llvm/CodeGen/MachineFunction.h>
+#include <llvm/CodeGen/MachineInstr.h>
+#include <llvm/CodeGen/MachineOperand.h>
+#include <llvm/CodeGen/TargetInstrInfo.h>
+#include <llvm/CodeGen/TargetSubtargetInfo.h>
+
+namespace {
+enum class MachineOperandKind {
+  kRegister,
+  kImmediate,
+  kBasicBlock,
+  kOther,
+};
+
+auto getMachineOperandKind(const llvm::MachineOperand &MO)
+    -> MachineOperandKind {
+  switch (MO.getType()) {
+  case llvm::MachineOperand::MO_Register:
+    return MachineOperandKind::kRegister;
+  case llvm::MachineOperand::MO_Immediate:
+    return MachineOperandKind::kImmediate;
+  case llvm::MachineOperand::MO_MachineBasicBlock:
+    return MachineOperandKind::kBasicBlock;
+  default:
+    return MachineOperandKind::kOther;
+  }
+}
+
+auto getNextBlock(llvm::MachineBasicBlock &MBB) -> llvm::MachineBasicBlock * {
+  const auto Next = std::next(MBB.getIterator());
+  return Next == MBB.getParent()->end() ? nullptr : &*Next;
+}
+
+// Bundled instructions are skipped, as the ones of a bundle are visited
+// through its header.
+auto getNextInstr(llvm::MachineInstr &MI) -> llvm::MachineInstr * {
+  const auto Next = std::next(llvm::MachineBasicBlock::iterator(MI));
+  return Next == MI.getParent()->end() ? nullptr : &*Next;
+}
+} // namespace
+#endif
@@ -1,10 +1,20 @@
 #pragma once
 
 #include <memory>
+#include <string>
 #include <type_traits>
 #include <utility>
 
+#include <llvm/ADT/STLExtras.h>
+#include <llvm/ADT/StringRef.h>
+#include <llvm/IR/Function.h>
+#include <llvm/IR/Module.h>
 #include <llvm/IR/PassManager.h>
+#include <llvm/Support/TimeProfiler.h>
+#include <llvm/Support/raw_ostream.h>
+
+#include "common.hh"
+#include "stack_trace.hh"
 
 namespace {
 enum class PreservedAnalyses {
@@ -20,21 +30,84 @@ template <typename IR> struct Pass : public llvm::PassInfoMixin<Pass<IR>> {
   using Entrypoint = PreservedAnalyses (*)(DataPtr, typename IR::Unit &,
                                            typename IR::AnalysisManager &);
 
-  Pass(Entrypoint Func, Data PassData) : PassData(std::move(PassData)) {
+  Pass(Entrypoint Func, Data PassData, std::string Name, bool Required)
+      : PassData(std::move(PassData)), Name(std::move(Name)) {
     this->Func = Func;
+    this->Required = Required;
   }
 
   auto run(typename IR::Unit &IrUnit, typename IR::AnalysisManager &AM)
       -> llvm::PreservedAnalyses {
+    const auto StackTrace =
+        PrettyStackTraceRustPass<IR>("pass", this->Name, IrUnit);
+    const auto TimeTrace = llvm::TimeTraceScope(
+        this->Name, [&] { return IrUnit.getName().str(); });
     return (this->Func(this->PassData.get(), IrUnit, AM) ==
                     PreservedAnalyses::kAll
                 ? llvm::PreservedAnalyses::all()
                 : llvm::PreservedAnalyses::none());
   }
 
+  auto getName() const -> llvm::StringRef { return this->Name; }
+
+  auto getRequired() const -> bool { return this->Required; }
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 14)
+  // Called by the pass model of LLVM, instead of the one of
+  // `llvm::PassInfoMixin` which prints the type name.
+  auto printPipeline(llvm::raw_ostream &OS,
+                     llvm::function_ref<llvm::StringRef(llvm::StringRef)>
+                         MapClassName2PassName) -> void {
+    OS << MapClassName2PassName(this->Name);
+  }
+#endif
+
 private:
   Entrypoint Func;
   Data PassData;
+  std::string Name;
+  bool Required;
 };
 
+// Pass model LLVM pass managers wrap `Pass<IR>` into.
+template <typename IR, typename PassT = Pass<IR>>
+using PassModelOf =
+    llvm::detail::PassModel<typename IR::Unit, PassT,
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR < 18)
+                            llvm::PreservedAnalyses,
+#endif
+                            typename IR::AnalysisManager>;
 } // namespace
+
+// The pass model of LLVM gets the name of a pass and whether it is required
+// from static functions of the pass type, which would be the same for every
+// Rust pass. No hook allows reporting the ones of each pass instance, so
+// these two members of the pass model are specialized.
+namespace llvm::detail {
+template <> auto PassModelOf<ModuleIR>::name() const -> llvm::StringRef {
+  return this->Pass.getName();
+}
+
+template <> auto PassModelOf<ModuleIR>::isRequired() const -> bool {
+  return this->Pass.getRequired();
+}
+
+template <> auto PassModelOf<FunctionIR>::name() const -> llvm::StringRef {
+  return this->Pass.getName();
+}
+
+template <> auto PassModelOf<FunctionIR>::isRequired() const -> bool {
+  return this->Pass.getRequired();
+}
+
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 19)
+template <>
+auto PassModelOf<MachineFunctionIR>::name() const -> llvm::StringRef {
+  return this->Pass.getName();
+}
+
+template <> auto PassModelOf<MachineFunctionIR>::isRequired() const -> bool {
+  return this->Pass.getRequired();
+}
+#endif
+} // namespace llvm::detail
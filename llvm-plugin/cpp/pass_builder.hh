@@ -0,0 +1,95 @@
+#pragma once
+
+#include <optional>
+
+#include <llvm/Passes/PassBuilder.h>
+#include <llvm/Support/PGOOptions.h>
+#include <llvm/Target/TargetMachine.h>
+
+namespace {
+struct PipelineTuningOptionsFFI {
+  bool LoopInterleaving;
+  bool LoopVectorization;
+  bool SLPVectorization;
+  bool LoopUnrolling;
+  bool CallGraphProfile;
+  bool MergeFunctions;
+};
+
+// Some members of `llvm::PassBuilder` have no accessor, and plugins are
+// only given the builder itself when registered. These members are accessed
+// through an explicit template instantiation, which is allowed to name
+// private members. This depends on the exact member names and types, so
+// each instantiation is limited to the LLVM versions it was checked against,
+// and the accessors fall back to "unknown" values on other versions.
+template <typename Tag, typename Tag::Type Member> struct PrivateMember {
+  friend auto getPrivateMember(Tag) -> typename Tag::Type { return Member; }
+};
+
+struct PassBuilderTM {
+  using Type = llvm::TargetMachine *llvm::PassBuilder::*;
+  friend auto getPrivateMember(PassBuilderTM) -> Type;
+};
+
+struct PassBuilderPTO {
+  using Type = llvm::PipelineTuningOptions llvm::PassBuilder::*;
+  friend auto getPrivateMember(PassBuilderPTO) -> Type;
+};
+
+struct PassBuilderPGOOpt {
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR >= 16)
+  using Type = std::optional<llvm::PGOOptions> llvm::PassBuilder::*;
+#else
+  using Type = llvm::Optional<llvm::PGOOptions> llvm::PassBuilder::*;
+#endif
+  friend auto getPrivateMember(PassBuilderPGOOpt) -> Type;
+};
+
+// `TargetMachine *TM` in LLVM 11 to 20.
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR <= 20)
+#define LLVM_HAS_PASS_BUILDER_TM
+template struct PrivateMember<PassBuilderTM, &llvm::PassBuilder::TM>;
+#endif
+
+// `PipelineTuningOptions PTO` in LLVM 11 to 20.
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR <= 20)
+#define LLVM_HAS_PASS_BUILDER_PTO
+template struct PrivateMember<PassBuilderPTO, &llvm::PassBuilder::PTO>;
+#endif
+
+// `Optional<PGOOptions> PGOOpt` in LLVM 11 to 15, which became
+// `std::optional<PGOOptions> PGOOpt` in LLVM 16 to 20.
+#if defined(LLVM_VERSION_MAJOR) && (LLVM_VERSION_MAJOR <= 20)
+#define LLVM_HAS_PASS_BUILDER_PGO_OPT
+template struct PrivateMember<PassBuilderPGOOpt, &llvm::PassBuilder::PGOOpt>;
+#endif
+
+auto getTargetMachine(const llvm::PassBuilder &Builder)
+    -> llvm::TargetMachine * {
+#ifdef LLVM_HAS_PASS_BUILDER_TM
+  return Builder.*getPrivateMember(PassBuilderTM{});
+#else
+  return nullptr;
+#endif
+}
+
+auto getPipelineTuningOptions(const llvm::PassBuilder &Builder)
+    -> const llvm::PipelineTuningOptions & {
+#ifdef LLVM_HAS_PASS_BUILDER_PTO
+  return Builder.*getPrivateMember(PassBuilderPTO{});
+#else
+  static const auto Default = llvm::PipelineTuningOptions();
+  return Default;
+#endif
+}
+
+auto getPGOOptions(const llvm::PassBuilder &Builder)
+    -> const llvm::PGOOptions * {
+#ifdef LLVM_HAS_PASS_BUILDER_PGO_OPT
+  const auto &PGOOpt = Builder.*getPrivateMember(PassBuilderPGOOpt{});
+  return PGOOpt ? &*PGOOpt : nullptr;
+#else
+  return nullptr;
+#endif
+}
+} // namespace
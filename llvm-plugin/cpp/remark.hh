@@ -0,0 +1,49 @@
+#pragma once
+
+#include <cstdint>
+#include <string>
+
+#include <llvm/ADT/StringRef.h>
+#include <llvm/Analysis/OptimizationRemarkEmitter.h>
+#include <llvm/IR/DiagnosticInfo.h>
+#include <llvm/IR/Function.h>
+#include <llvm/IR/Instruction.h>
+
+namespace {
+enum class RemarkKind {
+  kPassed,
+  kMissed,
+  kAnalysis,
+};
+
+struct RemarkArg {
+  const char *KeyPtr;
+  std::uintptr_t KeyLen;
+  const char *ValuePtr;
+  std::uintptr_t ValueLen;
+};
+
+template <typename RemarkT>
+auto emitRemark(llvm::OptimizationRemarkEmitter &ORE, llvm::Function &Function,
+                const std::string &PassName, llvm::StringRef RemarkName,
+                const llvm::Instruction *Inst, const RemarkArg *Args,
+                std::uintptr_t ArgsLen) -> void {
+  // Remarks without an instruction are attached to the function itself.
+  auto Remark =
+      Inst != nullptr
+          ? RemarkT(PassName.c_str(), RemarkName, Inst)
+          : RemarkT(PassName.c_str(), RemarkName,
+                    llvm::DiagnosticLocation(Function.getSubprogram()),
+                    &Function.getEntryBlock());
+
+  for (std::uintptr_t I = 0; I < ArgsLen; ++I) {
+    const auto &Arg = Args[I];
+    Remark.insert(llvm::DiagnosticInfoOptimizationBase::Argument(
+        llvm::StringRef(Arg.KeyPtr, Arg.KeyLen),
+        llvm::StringRef(Arg.ValuePtr, Arg.ValueLen)));
+  }
+
+  ORE.emit(Remark);
+}
+
+} // namespace
@@ -0,0 +1,37 @@
+#pragma once
+
+#include <string>
+
+#include <llvm/ADT/StringRef.h>
+#include <llvm/Support/PrettyStackTrace.h>
+#include <llvm/Support/raw_ostream.h>
+
+#include "common.hh"
+
+namespace {
+auto getPluginName() -> std::string & {
+  static auto Name = std::string();
+  return Name;
+}
+
+// Printed in the stack dump when LLVM crashes while running a Rust pass or
+// analysis, since the generic `Pass<IR>` frames don't tell which one.
+template <typename IR>
+class PrettyStackTraceRustPass : public llvm::PrettyStackTraceEntry {
+public:
+  PrettyStackTraceRustPass(const char *Kind, llvm::StringRef Name,
+                           const typename IR::Unit &IrUnit)
+      : Kind(Kind), Name(Name), IrUnit(IrUnit) {}
+
+  auto print(llvm::raw_ostream &OS) const -> void override {
+    OS << "Running " << this->Kind << " '" << this->Name << "' of plugin '"
+       << getPluginName() << "' on " << IR::UnitKind << " '"
+       << this->IrUnit.getName() << "'\n";
+  }
+
+private:
+  const char *Kind;
+  llvm::StringRef Name;
+  const typename IR::Unit &IrUnit;
+};
+} // namespace
@@ -0,0 +1,23 @@
+#pragma once
+
+#include <string>
+#include <utility>
+
+#include <llvm/ADT/Statistic.h>
+
+namespace {
+// Owns the strings referenced by the statistic, since LLVM only keeps
+// pointers to them until the statistics are printed at exit.
+struct Statistic {
+  Statistic(std::string DebugType, std::string Name, std::string Desc)
+      : DebugType(std::move(DebugType)), Name(std::move(Name)),
+        Desc(std::move(Desc)),
+        Inner(this->DebugType.c_str(), this->Name.c_str(),
+              this->Desc.c_str()) {}
+
+  std::string DebugType;
+  std::string Name;
+  std::string Desc;
+  llvm::TrackingStatistic Inner;
+};
+} // namespace
@@ -1,15 +1,21 @@
+use std::any::type_name;
 use std::ffi::c_void;
+use std::fmt::Debug;
+use std::marker::PhantomData;
 
-use inkwell::module::Module;
 use inkwell::values::{AsValueRef, FunctionValue};
 
-use crate::{LlvmFunctionAnalysis, LlvmModuleAnalysis};
+use crate::{
+    FunctionRef, LlvmFunctionAnalysis, LlvmModuleAnalysis, ModuleRef, OptimizationRemarkEmitter,
+    TargetMachineInfo,
+};
 
 /// Struct allowing to query the pass manager for the result of
 /// analyses on function IR.
 pub struct FunctionAnalysisManager {
     inner: *mut c_void,
     from_analysis_id: Option<crate::AnalysisKey>,
+    target_machine: *const TargetMachineInfo,
 }
 
 impl FunctionAnalysisManager {
@@ -17,23 +23,38 @@ impl FunctionAnalysisManager {
     pub unsafe fn from_raw(
         inner: *mut c_void,
         from_analysis_id: Option<crate::AnalysisKey>,
+        target_machine: Option<&TargetMachineInfo>,
     ) -> Self {
         Self {
             inner,
             from_analysis_id,
+            target_machine: target_machine.map_or(std::ptr::null(), |target| target),
         }
     }
 
+    /// Get a view of the target machine the pipeline was built for.
+    ///
+    /// Returns `None` when no target machine was given to LLVM, or when
+    /// the running pass was not added through a pass manager created by
+    /// a [PassBuilder](crate::PassBuilder).
+    pub fn target_machine(&self) -> Option<&TargetMachineInfo> {
+        unsafe { self.target_machine.as_ref() }
+    }
+
     /// Returns the result of the analysis on a given function IR.
     ///
     /// If the result is not in cache, the pass manager will execute the
     /// analysis pass. Otherwise, the result is directly returned from cache.
     ///
+    /// The returned reference borrows this manager, and therefore cannot
+    /// outlive the pass which was given access to it. Analysis results are
+    /// only invalidated once such pass returns.
+    ///
     /// # Panics
     ///
     /// Panics if the given analysis wasn't registered, or if this function was
     /// called within the given analysis itself.
-    pub fn get_result<A>(&self, function: &FunctionValue<'_>) -> &A::Result
+    pub fn get_result<'a, A>(&'a self, function: &FunctionValue<'_>) -> &'a A::Result
     where
         A: crate::LlvmFunctionAnalysis,
     {
@@ -43,11 +64,10 @@ impl FunctionAnalysisManager {
             "Analysis cannot request its own result"
         );
 
-        unsafe {
-            let res =
-                crate::get_function_analysis_result(self.inner, id, function.as_value_ref().cast());
-            Box::leak(Box::from_raw(res.cast()))
-        }
+        let res =
+            crate::get_function_analysis_result(self.inner, id, function.as_value_ref().cast());
+
+        unsafe { &*res.cast::<A::Result>() }
     }
 
     /// Returns the result of the analysis on a given function IR.
@@ -57,11 +77,14 @@ impl FunctionAnalysisManager {
     ///
     /// This function never triggers the execution of an analysis.
     ///
+    /// The returned reference borrows this manager, and therefore cannot
+    /// outlive the pass which was given access to it.
+    ///
     /// # Panics
     ///
     /// Panics if the given analysis wasn't registered, or if this function was
     /// called within the given analysis itself.
-    pub fn get_cached_result<A>(&self, function: &FunctionValue<'_>) -> Option<&A::Result>
+    pub fn get_cached_result<'a, A>(&'a self, function: &FunctionValue<'_>) -> Option<&'a A::Result>
     where
         A: crate::LlvmFunctionAnalysis,
     {
@@ -78,13 +101,25 @@ impl FunctionAnalysisManager {
         );
 
         if !res.is_null() {
-            let res = unsafe { Box::leak(Box::from_raw(res.cast())) };
-            Some(res)
+            Some(unsafe { &*res.cast::<A::Result>() })
         } else {
             None
         }
     }
 
+    /// Returns an [OptimizationRemarkEmitter] for the given function IR.
+    ///
+    /// This queries the builtin `OptimizationRemarkEmitterAnalysis`, which
+    /// is registered by default by the LLVM tools.
+    pub fn get_optimization_remark_emitter(
+        &self,
+        function: &FunctionValue<'_>,
+    ) -> OptimizationRemarkEmitter<'_> {
+        let function = function.as_value_ref().cast();
+        let emitter = unsafe { crate::getOptimizationRemarkEmitter(self.inner, function) };
+        unsafe { OptimizationRemarkEmitter::from_raw(emitter, function) }
+    }
+
     /// Register an analysis pass to the analysis manager.
     ///
     /// # Panics
@@ -94,17 +129,21 @@ impl FunctionAnalysisManager {
     where
         T: LlvmFunctionAnalysis,
     {
-        let pass = Box::new(pass);
+        let pass = Box::new((pass, self.target_machine().cloned()));
 
         extern "C" fn result_deleter<T>(data: *mut c_void)
         where
             T: LlvmFunctionAnalysis,
         {
-            drop(unsafe { Box::<<T as LlvmFunctionAnalysis>::Result>::from_raw(data.cast()) })
+            crate::unwind::catch_unwind("function analysis result", type_name::<T>(), || {
+                drop(unsafe { Box::<<T as LlvmFunctionAnalysis>::Result>::from_raw(data.cast()) })
+            })
         }
 
         extern "C" fn pass_deleter<T>(pass: *mut c_void) {
-            drop(unsafe { Box::<T>::from_raw(pass.cast()) })
+            crate::unwind::catch_unwind("function analysis", type_name::<T>(), || {
+                drop(unsafe { Box::<(T, Option<TargetMachineInfo>)>::from_raw(pass.cast()) })
+            })
         }
 
         extern "C" fn pass_entrypoint<T>(
@@ -116,23 +155,24 @@ impl FunctionAnalysisManager {
         ) where
             T: LlvmFunctionAnalysis,
         {
-            let pass = unsafe { Box::<T>::from_raw(pass.cast()) };
-            let function = unsafe { FunctionValue::new(function.cast()).unwrap() };
-            let manager = unsafe { FunctionAnalysisManager::from_raw(manager, Some(T::id())) };
+            let (pass, target) = unsafe { &*pass.cast::<(T, Option<TargetMachineInfo>)>() };
 
-            let data = pass.run_analysis(&function, &manager);
+            let data = crate::unwind::catch_unwind("function analysis", type_name::<T>(), || {
+                let function = unsafe { FunctionRef::from_raw(function) };
+                let manager = unsafe {
+                    FunctionAnalysisManager::from_raw(manager, Some(T::id()), target.as_ref())
+                };
+                pass.run_analysis(&function, &manager)
+            });
 
             let data = Box::new(data);
             unsafe {
                 *res = Box::<<T as LlvmFunctionAnalysis>::Result>::into_raw(data).cast();
                 *res_deleter = result_deleter::<T>;
             }
-
-            let _ = Box::into_raw(pass);
-            #[allow(forgetting_copy_types)]
-            std::mem::forget(function);
         }
 
+        let name = type_name::<T>();
         let success = unsafe {
             super::functionAnalysisManagerRegisterPass(
                 self.inner,
@@ -140,10 +180,32 @@ impl FunctionAnalysisManager {
                 pass_deleter::<T>,
                 pass_entrypoint::<T>,
                 T::id(),
+                name.as_ptr(),
+                name.len(),
             )
         };
 
         assert!(success, "analysis already registered");
+        crate::analysis_pipeline::register_function_analysis::<T>(None);
+    }
+
+    /// Register an analysis pass to the analysis manager, also making its
+    /// result printable from textual pipelines with `print<name>`.
+    ///
+    /// See [LlvmFunctionAnalysis::PIPELINE_NAME].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given analysis was already registered.
+    pub fn register_printable_pass<T>(&mut self, pass: T)
+    where
+        T: LlvmFunctionAnalysis,
+        T::Result: Debug,
+    {
+        self.register_pass(pass);
+        crate::analysis_pipeline::register_function_analysis::<T>(Some(
+            crate::analysis_pipeline::add_print_function_analysis::<T>,
+        ));
     }
 }
 
@@ -152,6 +214,7 @@ impl FunctionAnalysisManager {
 pub struct ModuleAnalysisManager {
     inner: *mut c_void,
     from_analysis_id: Option<crate::AnalysisKey>,
+    target_machine: *const TargetMachineInfo,
 }
 
 impl ModuleAnalysisManager {
@@ -159,23 +222,38 @@ impl ModuleAnalysisManager {
     pub unsafe fn from_raw(
         inner: *mut c_void,
         from_analysis_id: Option<crate::AnalysisKey>,
+        target_machine: Option<&TargetMachineInfo>,
     ) -> Self {
         Self {
             inner,
             from_analysis_id,
+            target_machine: target_machine.map_or(std::ptr::null(), |target| target),
         }
     }
 
+    /// Get a view of the target machine the pipeline was built for.
+    ///
+    /// Returns `None` when no target machine was given to LLVM, or when
+    /// the running pass was not added through a pass manager created by
+    /// a [PassBuilder](crate::PassBuilder).
+    pub fn target_machine(&self) -> Option<&TargetMachineInfo> {
+        unsafe { self.target_machine.as_ref() }
+    }
+
     /// Returns the result of the analysis on a given module IR.
     ///
     /// If the result is not in cache, the pass manager will execute the
     /// analysis pass. Otherwise, the result is directly returned from cache.
     ///
+    /// The returned reference borrows this manager, and therefore cannot
+    /// outlive the pass which was given access to it. Analysis results are
+    /// only invalidated once such pass returns.
+    ///
     /// # Panics
     ///
     /// Panics if the given analysis wasn't registered, or if this function was
     /// called within the given analysis itself.
-    pub fn get_result<A>(&self, module: &Module<'_>) -> &A::Result
+    pub fn get_result<'a, A>(&'a self, module: &ModuleRef<'_>) -> &'a A::Result
     where
         A: crate::LlvmModuleAnalysis,
     {
@@ -188,7 +266,7 @@ impl ModuleAnalysisManager {
         let res =
             crate::get_module_analysis_result(self.inner, A::id(), module.as_mut_ptr().cast());
 
-        unsafe { Box::leak(Box::from_raw(res.cast())) }
+        unsafe { &*res.cast::<A::Result>() }
     }
 
     /// Returns the result of the analysis on a given module IR.
@@ -198,11 +276,14 @@ impl ModuleAnalysisManager {
     ///
     /// This function never triggers the execution of an analysis.
     ///
+    /// The returned reference borrows this manager, and therefore cannot
+    /// outlive the pass which was given access to it.
+    ///
     /// # Panics
     ///
     /// Panics if the given analysis wasn't registered, or if this function was
     /// called within the given analysis itself.
-    pub fn get_cached_result<A>(&self, module: &Module<'_>) -> Option<&A::Result>
+    pub fn get_cached_result<'a, A>(&'a self, module: &ModuleRef<'_>) -> Option<&'a A::Result>
     where
         A: crate::LlvmModuleAnalysis,
     {
@@ -219,8 +300,7 @@ impl ModuleAnalysisManager {
         );
 
         if !res.is_null() {
-            let res = unsafe { Box::leak(Box::from_raw(res.cast())) };
-            Some(res)
+            Some(unsafe { &*res.cast::<A::Result>() })
         } else {
             None
         }
@@ -228,15 +308,26 @@ impl ModuleAnalysisManager {
 
     /// Returns a [FunctionAnalysisManagerProxy], which is essentially an interface
     /// allowing management of analyses at the function level.
+    ///
+    /// The returned proxy borrows this manager, so that the function-level
+    /// analysis results it gives access to cannot outlive the current pass.
     pub fn get_function_analysis_manager_proxy(
         &self,
-        module: &Module<'_>,
-    ) -> FunctionAnalysisManagerProxy {
+        module: &ModuleRef<'_>,
+    ) -> FunctionAnalysisManagerProxy<'_> {
         let proxy = crate::get_function_analysis_manager_module_proxy(
             self.inner,
             module.as_mut_ptr().cast(),
         );
-        FunctionAnalysisManagerProxy { inner: proxy }
+        let manager = crate::get_function_analysis_manager(proxy);
+        FunctionAnalysisManagerProxy {
+            manager: FunctionAnalysisManager {
+                inner: manager,
+                from_analysis_id: None,
+                target_machine: self.target_machine,
+            },
+            _marker: PhantomData,
+        }
     }
 
     /// Register an analysis pass to the analysis manager.
@@ -248,17 +339,21 @@ impl ModuleAnalysisManager {
     where
         T: LlvmModuleAnalysis,
     {
-        let pass = Box::new(pass);
+        let pass = Box::new((pass, self.target_machine().cloned()));
 
         extern "C" fn result_deleter<T>(data: *mut c_void)
         where
             T: LlvmModuleAnalysis,
         {
-            drop(unsafe { Box::<<T as LlvmModuleAnalysis>::Result>::from_raw(data.cast()) })
+            crate::unwind::catch_unwind("module analysis result", type_name::<T>(), || {
+                drop(unsafe { Box::<<T as LlvmModuleAnalysis>::Result>::from_raw(data.cast()) })
+            })
         }
 
         extern "C" fn pass_deleter<T>(pass: *mut c_void) {
-            drop(unsafe { Box::<T>::from_raw(pass.cast()) })
+            crate::unwind::catch_unwind("module analysis", type_name::<T>(), || {
+                drop(unsafe { Box::<(T, Option<TargetMachineInfo>)>::from_raw(pass.cast()) })
+            })
         }
 
         extern "C" fn pass_entrypoint<T>(
@@ -270,22 +365,24 @@ impl ModuleAnalysisManager {
         ) where
             T: LlvmModuleAnalysis,
         {
-            let pass = unsafe { Box::<T>::from_raw(pass.cast()) };
-            let module = unsafe { Module::new(module.cast()) };
-            let manager = unsafe { ModuleAnalysisManager::from_raw(manager, Some(T::id())) };
+            let (pass, target) = unsafe { &*pass.cast::<(T, Option<TargetMachineInfo>)>() };
 
-            let data = pass.run_analysis(&module, &manager);
+            let data = crate::unwind::catch_unwind("module analysis", type_name::<T>(), || {
+                let module = unsafe { ModuleRef::from_raw(module) };
+                let manager = unsafe {
+                    ModuleAnalysisManager::from_raw(manager, Some(T::id()), target.as_ref())
+                };
+                pass.run_analysis(&module, &manager)
+            });
 
             let data = Box::new(data);
             unsafe {
                 *res = Box::<<T as LlvmModuleAnalysis>::Result>::into_raw(data).cast();
                 *res_deleter = result_deleter::<T>;
             }
-
-            let _ = Box::into_raw(pass);
-            std::mem::forget(module);
         }
 
+        let name = type_name::<T>();
         let success = unsafe {
             super::moduleAnalysisManagerRegisterPass(
                 self.inner,
@@ -293,10 +390,32 @@ impl ModuleAnalysisManager {
                 pass_deleter::<T>,
                 pass_entrypoint::<T>,
                 T::id(),
+                name.as_ptr(),
+                name.len(),
             )
         };
 
         assert!(success, "analysis already registered");
+        crate::analysis_pipeline::register_module_analysis::<T>(None);
+    }
+
+    /// Register an analysis pass to the analysis manager, also making its
+    /// result printable from textual pipelines with `print<name>`.
+    ///
+    /// See [LlvmModuleAnalysis::PIPELINE_NAME].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given analysis was already registered.
+    pub fn register_printable_pass<T>(&mut self, pass: T)
+    where
+        T: LlvmModuleAnalysis,
+        T::Result: Debug,
+    {
+        self.register_pass(pass);
+        crate::analysis_pipeline::register_module_analysis::<T>(Some(
+            crate::analysis_pipeline::add_print_module_analysis::<T>,
+        ));
     }
 }
 
@@ -309,22 +428,19 @@ impl ModuleAnalysisManager {
 /// # Example
 ///
 /// ```
-/// # use llvm_plugin::inkwell::module::Module;
-/// # use llvm_plugin::inkwell::values::FunctionValue;
 /// # use llvm_plugin::{
-/// #    AnalysisKey, FunctionAnalysisManager, LlvmFunctionAnalysis, LlvmModulePass,
-/// #    ModuleAnalysisManager, PreservedAnalyses,
+/// #    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis, LlvmModulePass,
+/// #    ModuleAnalysisManager, ModuleRef, PreservedAnalyses,
 /// # };
 /// struct Pass;
 /// impl LlvmModulePass for Pass {
 ///     fn run_pass(
 ///         &self,
-///         module: &mut Module,
+///         module: &mut ModuleRef,
 ///         manager: &ModuleAnalysisManager,
 ///     ) -> PreservedAnalyses {
-///         let manager = manager
-///             .get_function_analysis_manager_proxy(&module)
-///             .get_manager();
+///         let proxy = manager.get_function_analysis_manager_proxy(&module);
+///         let manager = proxy.get_manager();
 ///
 ///         let function = module.get_first_function().unwrap();
 ///         let result = manager.get_result::<Analysis>(&function);
@@ -340,7 +456,7 @@ impl ModuleAnalysisManager {
 ///
 ///     fn run_analysis(
 ///         &self,
-///         _function: &FunctionValue,
+///         _function: &FunctionRef,
 ///         _manager: &FunctionAnalysisManager,
 ///     ) -> Self::Result {
 ///         "Some result".to_owned()
@@ -351,17 +467,43 @@ impl ModuleAnalysisManager {
 ///     }
 /// }
 /// ```
-pub struct FunctionAnalysisManagerProxy {
-    inner: *mut c_void,
+///
+/// Function-level analysis results are tied to the borrow of the module-level
+/// manager, so they cannot be kept around once the pass returns:
+///
+/// ```compile_fail
+/// # use llvm_plugin::{
+/// #    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis,
+/// #    ModuleAnalysisManager, ModuleRef,
+/// # };
+/// # struct Analysis;
+/// # impl LlvmFunctionAnalysis for Analysis {
+/// #     type Result = String;
+/// #     fn run_analysis(
+/// #         &self,
+/// #         _function: &FunctionRef,
+/// #         _manager: &FunctionAnalysisManager,
+/// #     ) -> Self::Result {
+/// #         "Some result".to_owned()
+/// #     }
+/// #     fn id() -> AnalysisKey {
+/// #         1 as AnalysisKey
+/// #     }
+/// # }
+/// fn escape<'a>(module: &ModuleRef<'a>, manager: &ModuleAnalysisManager) -> &'a String {
+///     let proxy = manager.get_function_analysis_manager_proxy(module);
+///     let function = module.get_first_function().unwrap();
+///     proxy.get_manager().get_result::<Analysis>(&function)
+/// }
+/// ```
+pub struct FunctionAnalysisManagerProxy<'a> {
+    manager: FunctionAnalysisManager,
+    _marker: PhantomData<&'a ModuleAnalysisManager>,
 }
 
-impl FunctionAnalysisManagerProxy {
+impl FunctionAnalysisManagerProxy<'_> {
     /// Returns the inner [FunctionAnalysisManager].
-    pub fn get_manager(&self) -> FunctionAnalysisManager {
-        let manager = crate::get_function_analysis_manager(self.inner);
-        FunctionAnalysisManager {
-            inner: manager,
-            from_analysis_id: None,
-        }
+    pub fn get_manager(&self) -> &FunctionAnalysisManager {
+        &self.manager
     }
 }
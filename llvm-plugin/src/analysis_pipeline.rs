@@ -0,0 +1,264 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::{Mutex, PoisonError};
+
+use crate::{
+    FunctionAnalysisManager, FunctionPassManager, FunctionRef, LlvmFunctionAnalysis,
+    LlvmFunctionPass, LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, ModulePassManager,
+    ModuleRef, PipelineParsing, PreservedAnalyses,
+};
+
+/// Pipeline elements available for an analysis with a pipeline name.
+struct AnalysisPipelineElements<M> {
+    name: &'static str,
+    require: fn(&mut M),
+    invalidate: fn(&mut M),
+    print: Option<fn(&mut M)>,
+}
+
+type Registry<M> = Mutex<Vec<AnalysisPipelineElements<M>>>;
+
+static MODULE_ANALYSES: Registry<ModulePassManager> = Mutex::new(Vec::new());
+static FUNCTION_ANALYSES: Registry<FunctionPassManager> = Mutex::new(Vec::new());
+
+pub(crate) fn register_module_analysis<T>(printable: Option<fn(&mut ModulePassManager)>)
+where
+    T: LlvmModuleAnalysis,
+{
+    if let Some(name) = T::PIPELINE_NAME {
+        register(
+            &MODULE_ANALYSES,
+            AnalysisPipelineElements {
+                name,
+                require: |manager| manager.add_pass(RequireModuleAnalysis::<T>::new()),
+                invalidate: |manager| manager.add_invalidate_analysis_pass(T::id()),
+                print: printable,
+            },
+        );
+    }
+}
+
+pub(crate) fn register_function_analysis<T>(printable: Option<fn(&mut FunctionPassManager)>)
+where
+    T: LlvmFunctionAnalysis,
+{
+    if let Some(name) = T::PIPELINE_NAME {
+        register(
+            &FUNCTION_ANALYSES,
+            AnalysisPipelineElements {
+                name,
+                require: |manager| manager.add_pass(RequireFunctionAnalysis::<T>::new()),
+                invalidate: |manager| manager.add_invalidate_analysis_pass(T::id()),
+                print: printable,
+            },
+        );
+    }
+}
+
+pub(crate) fn add_print_module_analysis<T>(manager: &mut ModulePassManager)
+where
+    T: LlvmModuleAnalysis,
+    T::Result: Debug,
+{
+    manager.add_pass(PrintModuleAnalysis::<T>::new());
+}
+
+pub(crate) fn add_print_function_analysis<T>(manager: &mut FunctionPassManager)
+where
+    T: LlvmFunctionAnalysis,
+    T::Result: Debug,
+{
+    manager.add_pass(PrintFunctionAnalysis::<T>::new());
+}
+
+pub(crate) fn parse_module_analysis_element(
+    name: &str,
+    manager: &mut ModulePassManager,
+) -> PipelineParsing {
+    parse(&MODULE_ANALYSES, name, manager)
+}
+
+pub(crate) fn parse_function_analysis_element(
+    name: &str,
+    manager: &mut FunctionPassManager,
+) -> PipelineParsing {
+    parse(&FUNCTION_ANALYSES, name, manager)
+}
+
+fn register<M>(registry: &Registry<M>, elements: AnalysisPipelineElements<M>) {
+    let mut registry = registry.lock().unwrap_or_else(PoisonError::into_inner);
+    registry.retain(|e| e.name != elements.name);
+    registry.push(elements);
+}
+
+fn parse<M>(registry: &Registry<M>, name: &str, manager: &mut M) -> PipelineParsing {
+    let Some((kind, name)) = name.strip_suffix('>').and_then(|n| n.split_once('<')) else {
+        return PipelineParsing::NotParsed;
+    };
+
+    let add = {
+        let registry = registry.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(elements) = registry.iter().find(|e| e.name == name) else {
+            return PipelineParsing::NotParsed;
+        };
+
+        match kind {
+            "require" => elements.require,
+            "invalidate" => elements.invalidate,
+            "print" => match elements.print {
+                Some(print) => print,
+                None => return PipelineParsing::NotParsed,
+            },
+            _ => return PipelineParsing::NotParsed,
+        }
+    };
+
+    add(manager);
+    PipelineParsing::Parsed
+}
+
+struct RequireModuleAnalysis<T> {
+    name: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: LlvmModuleAnalysis> RequireModuleAnalysis<T> {
+    fn new() -> Self {
+        Self {
+            name: format!("require<{}>", T::PIPELINE_NAME.unwrap_or_default()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: LlvmModuleAnalysis> LlvmModulePass for RequireModuleAnalysis<T> {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef<'_>,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        manager.get_result::<T>(module);
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    const REQUIRED: bool = true;
+}
+
+struct RequireFunctionAnalysis<T> {
+    name: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: LlvmFunctionAnalysis> RequireFunctionAnalysis<T> {
+    fn new() -> Self {
+        Self {
+            name: format!("require<{}>", T::PIPELINE_NAME.unwrap_or_default()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: LlvmFunctionAnalysis> LlvmFunctionPass for RequireFunctionAnalysis<T> {
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef<'_>,
+        manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        manager.get_result::<T>(function);
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    const REQUIRED: bool = true;
+}
+
+struct PrintModuleAnalysis<T> {
+    name: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: LlvmModuleAnalysis> PrintModuleAnalysis<T> {
+    fn new() -> Self {
+        Self {
+            name: format!("print<{}>", T::PIPELINE_NAME.unwrap_or_default()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> LlvmModulePass for PrintModuleAnalysis<T>
+where
+    T: LlvmModuleAnalysis,
+    T::Result: Debug,
+{
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef<'_>,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        let result = manager.get_result::<T>(module);
+        write_errs(&format!(
+            "Printing analysis '{}' for module '{}':\n{:#?}\n",
+            T::PIPELINE_NAME.unwrap_or_default(),
+            module.get_name().to_string_lossy(),
+            result,
+        ));
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+struct PrintFunctionAnalysis<T> {
+    name: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: LlvmFunctionAnalysis> PrintFunctionAnalysis<T> {
+    fn new() -> Self {
+        Self {
+            name: format!("print<{}>", T::PIPELINE_NAME.unwrap_or_default()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> LlvmFunctionPass for PrintFunctionAnalysis<T>
+where
+    T: LlvmFunctionAnalysis,
+    T::Result: Debug,
+{
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef<'_>,
+        manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        let result = manager.get_result::<T>(function);
+        write_errs(&format!(
+            "Printing analysis '{}' for function '{}':\n{:#?}\n",
+            T::PIPELINE_NAME.unwrap_or_default(),
+            function.get_name().to_string_lossy(),
+            result,
+        ));
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+// Prints to LLVM's stderr stream, so that the output is ordered with the one
+// of LLVM (e.g. `print<...>` passes of builtin analyses).
+fn write_errs(text: &str) {
+    unsafe { super::llvmErrsWrite(text.as_ptr(), text.len()) }
+}
@@ -0,0 +1,137 @@
+use std::sync::OnceLock;
+
+/// Named counter gating individual transformations of a pass.
+///
+/// Counters are controlled with the `-debug-counter` option of LLVM tools,
+/// which allows bisecting a miscompile down to a single rewrite. Its syntax
+/// depends on the LLVM version:
+///
+/// - LLVM 19 and later take the (0-based) indices of the calls to
+///   [should_execute](DebugCounter::should_execute) returning `true`, e.g.
+///   `-debug-counter=my-pass-rewrite=5` only lets the 6th call through, and
+///   `-debug-counter=my-pass-rewrite=2-4:7` the 3rd to 5th and 8th ones.
+/// - LLVM 11 to 18 take a number of calls to skip and a number of calls to
+///   let through afterwards, e.g.
+///   `-debug-counter=my-pass-rewrite-skip=5,my-pass-rewrite-count=1` only lets
+///   the 6th call through.
+///
+/// LLVM only accepts counters registered before parsing its command line.
+/// Counters should therefore be declared with the [debug_counter] macro,
+/// which registers them when the plugin is loaded. This also means the
+/// `-debug-counter` option must come after `--load-pass-plugin` on the
+/// command line.
+///
+/// Counters are no-ops when the `-debug-counter` option is not given.
+///
+/// [debug_counter]: crate::debug_counter
+pub struct DebugCounter {
+    name: &'static str,
+    description: &'static str,
+    id: OnceLock<u32>,
+}
+
+impl DebugCounter {
+    /// Create a new counter, which is not yet registered to LLVM.
+    ///
+    /// Prefer the [debug_counter](crate::debug_counter) macro, which also
+    /// registers the counter when the plugin is loaded.
+    pub const fn new(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            id: OnceLock::new(),
+        }
+    }
+
+    /// Register this counter to LLVM, if not already done.
+    pub fn register(&self) {
+        self.id();
+    }
+
+    /// Whether the gated transformation should be performed.
+    ///
+    /// Each call increments the counter.
+    pub fn should_execute(&self) -> bool {
+        unsafe { super::debugCounterShouldExecute(self.id()) }
+    }
+
+    /// Get the name of this counter.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn id(&self) -> u32 {
+        *self.id.get_or_init(|| unsafe {
+            super::debugCounterRegister(
+                self.name.as_ptr(),
+                self.name.len(),
+                self.description.as_ptr(),
+                self.description.len(),
+            )
+        })
+    }
+}
+
+/// Declare a static [DebugCounter], registered when the plugin is loaded.
+///
+/// Registration relies on the static constructors of the platform, so this
+/// macro fails to compile on targets other than Linux, Android, the BSDs,
+/// illumos, macOS, iOS and Windows.
+///
+/// # Example
+///
+/// ```no_run
+/// llvm_plugin::debug_counter!(
+///     static REWRITE_COUNTER = "my-pass-rewrite", "Controls rewrites of my-pass"
+/// );
+///
+/// if REWRITE_COUNTER.should_execute() {
+///     // perform the rewrite
+/// }
+/// ```
+#[macro_export]
+macro_rules! debug_counter {
+    ($vis:vis static $ident:ident = $name:expr, $desc:expr $(,)?) => {
+        $vis static $ident: $crate::DebugCounter = $crate::DebugCounter::new($name, $desc);
+
+        const _: () = {
+            extern "C" fn register() {
+                $ident.register();
+            }
+
+            #[cfg(not(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_os = "dragonfly",
+                target_os = "illumos",
+                target_os = "macos",
+                target_os = "ios",
+                target_os = "windows",
+            )))]
+            compile_error!("`debug_counter!` is not supported on this target");
+
+            #[used]
+            #[cfg_attr(
+                any(
+                    target_os = "linux",
+                    target_os = "android",
+                    target_os = "freebsd",
+                    target_os = "netbsd",
+                    target_os = "openbsd",
+                    target_os = "dragonfly",
+                    target_os = "illumos",
+                ),
+                link_section = ".init_array"
+            )]
+            #[cfg_attr(
+                any(target_os = "macos", target_os = "ios"),
+                link_section = "__DATA,__mod_init_func"
+            )]
+            #[cfg_attr(target_os = "windows", link_section = ".CRT$XCU")]
+            static REGISTER: extern "C" fn() = register;
+        };
+    };
+}
@@ -0,0 +1,290 @@
+use std::ffi::c_void;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use inkwell::values::{AsValueRef, InstructionValue};
+
+/// Enum specifying the severity of a diagnostic emitted through the
+/// [LLVMContext] of some IR unit.
+///
+/// The way diagnostics are reported depends on the LLVM tool running the
+/// plugin. For instance, [opt] prints them on stderr, and exits with a
+/// failure status when an error is reported.
+///
+/// [LLVMContext]: https://llvm.org/doxygen/classllvm_1_1LLVMContext.html
+/// [opt]: https://www.llvm.org/docs/CommandGuide/opt.html
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    /// Unrecoverable issue.
+    Error,
+
+    /// Issue which doesn't prevent the pipeline from running.
+    Warning,
+
+    /// Informational message about a transformation.
+    Remark,
+
+    /// Additional information attached to a previous diagnostic.
+    Note,
+}
+
+/// Source location attached to a diagnostic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiagnosticLocation {
+    file: String,
+    line: u32,
+    column: u32,
+}
+
+impl DiagnosticLocation {
+    /// Creates a new source location.
+    pub fn new(file: impl Into<String>, line: u32, column: u32) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+
+    /// Returns the source location attached to the given instruction.
+    ///
+    /// The file name is joined to the compilation directory recorded in the
+    /// debug info. Only the file, line and column are kept: diagnostics
+    /// emitted with this location through the IR unit containing the
+    /// instruction are attached to its debug location again, which LLVM
+    /// consumers can read instead of parsing the message.
+    ///
+    /// Returns `None` if the instruction doesn't have a debug location, which
+    /// is usually the case when the IR was compiled without debug info.
+    pub fn from_instruction(instruction: &InstructionValue<'_>) -> Option<Self> {
+        let mut dir_ptr = std::ptr::null();
+        let mut dir_len = 0;
+        let mut file_ptr = std::ptr::null();
+        let mut file_len = 0;
+        let mut line = 0;
+        let mut column = 0;
+
+        let found = unsafe {
+            super::instructionGetDebugLoc(
+                instruction.as_value_ref().cast(),
+                &mut dir_ptr,
+                &mut dir_len,
+                &mut file_ptr,
+                &mut file_len,
+                &mut line,
+                &mut column,
+            )
+        };
+        if !found {
+            return None;
+        }
+
+        let dir = unsafe { String::from_utf8_lossy(std::slice::from_raw_parts(dir_ptr, dir_len)) };
+        let file =
+            unsafe { String::from_utf8_lossy(std::slice::from_raw_parts(file_ptr, file_len)) };
+        let file = Path::new(&*dir).join(&*file);
+
+        Some(Self {
+            file: file.to_string_lossy().into_owned(),
+            line,
+            column,
+        })
+    }
+
+    /// Returns the source file name.
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// Returns the line number.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the column number.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+}
+
+/// Error returned by fallible passes.
+///
+/// Such error is reported as a diagnostic through the [LLVMContext] of the
+/// IR unit the pass was running on. By default, its severity is
+/// [DiagnosticSeverity::Error], which makes the LLVM tool (e.g. [opt],
+/// [clang]) exit with a failure status.
+///
+/// [LLVMContext]: https://llvm.org/doxygen/classllvm_1_1LLVMContext.html
+/// [opt]: https://www.llvm.org/docs/CommandGuide/opt.html
+/// [clang]: https://clang.llvm.org/
+#[derive(Clone, Debug)]
+pub struct PassError {
+    severity: DiagnosticSeverity,
+    message: String,
+    location: Option<DiagnosticLocation>,
+}
+
+impl PassError {
+    /// Creates a new error with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Error,
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    /// Sets the severity of the reported diagnostic.
+    pub fn with_severity(mut self, severity: DiagnosticSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Attaches a source location to the reported diagnostic.
+    pub fn with_location(mut self, location: DiagnosticLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Returns the severity of the reported diagnostic.
+    pub fn severity(&self) -> DiagnosticSeverity {
+        self.severity
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the source location attached to the reported diagnostic.
+    pub fn location(&self) -> Option<&DiagnosticLocation> {
+        self.location.as_ref()
+    }
+
+    pub(crate) fn emit(&self, diagnostics: &Diagnostics<'_>) {
+        diagnostics.emit(self.severity, &self.message, self.location.as_ref());
+    }
+}
+
+impl fmt::Display for PassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PassError {}
+
+impl From<String> for PassError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for PassError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+/// Handle allowing to emit diagnostics through the [LLVMContext] of an IR
+/// unit.
+///
+/// It is obtained from [ModuleRef::diagnostics] or [FunctionRef::diagnostics],
+/// and is therefore available to both passes and analyses.
+///
+/// Diagnostics are reported the same way as those emitted by builtin passes.
+/// When given a location, the message is prefixed with `file:line:column: `.
+/// Locations matching the debug location of an instruction of the IR unit
+/// (e.g. obtained with [DiagnosticLocation::from_instruction]) are also
+/// attached to the diagnostic as a debug location, which LLVM consumers can
+/// read (see `DiagnosticInfoWithLocationBase`).
+///
+/// # Example
+///
+/// ```
+/// # use llvm_plugin::{DiagnosticLocation, DiagnosticSeverity, FunctionRef};
+/// fn lint(function: &FunctionRef) {
+///     let diagnostics = function.diagnostics();
+///
+///     for bb in function.get_basic_blocks() {
+///         for inst in bb.get_instructions() {
+///             let location = DiagnosticLocation::from_instruction(&inst);
+///             diagnostics.emit(
+///                 DiagnosticSeverity::Warning,
+///                 "suspicious instruction",
+///                 location.as_ref(),
+///             );
+///         }
+///     }
+/// }
+/// ```
+///
+/// [LLVMContext]: https://llvm.org/doxygen/classllvm_1_1LLVMContext.html
+/// [ModuleRef::diagnostics]: crate::ModuleRef::diagnostics
+/// [FunctionRef::diagnostics]: crate::FunctionRef::diagnostics
+/// [clang]: https://clang.llvm.org/
+#[derive(Clone, Copy)]
+pub struct Diagnostics<'a> {
+    context: *mut c_void,
+    module: *mut c_void,
+    function: *mut c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl Diagnostics<'_> {
+    pub(crate) unsafe fn from_context(context: *mut c_void) -> Self {
+        Self {
+            context,
+            module: std::ptr::null_mut(),
+            function: std::ptr::null_mut(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) unsafe fn from_module(module: *mut c_void) -> Self {
+        Self {
+            module,
+            ..Self::from_context(super::moduleGetContext(module))
+        }
+    }
+
+    pub(crate) unsafe fn from_function(function: *mut c_void) -> Self {
+        Self {
+            function,
+            ..Self::from_context(super::functionGetContext(function))
+        }
+    }
+
+    /// Emits a diagnostic with the given severity and message, and an optional
+    /// source location.
+    ///
+    /// Depending on the LLVM tool, emitting a diagnostic with the
+    /// [DiagnosticSeverity::Error] severity may exit the process.
+    pub fn emit(
+        &self,
+        severity: DiagnosticSeverity,
+        message: &str,
+        location: Option<&DiagnosticLocation>,
+    ) {
+        let (file_ptr, file_len, line, column) = match location {
+            Some(loc) => (loc.file.as_ptr(), loc.file.len(), loc.line, loc.column),
+            None => (std::ptr::null(), 0, 0, 0),
+        };
+        unsafe {
+            super::llvmContextDiagnose(
+                self.context,
+                self.module,
+                self.function,
+                severity,
+                message.as_ptr(),
+                message.len(),
+                file_ptr,
+                file_len,
+                line,
+                column,
+            )
+        }
+    }
+}
@@ -183,11 +183,249 @@ extern "C" {
         cb_sys: extern "C" fn(*const c_void, *const u8, usize, *mut c_void) -> bool,
     );
 
+    pub(crate) fn passBuilderAddTopLevelPipelineParsingCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(*const c_void, *mut c_void, *const c_void, usize) -> bool,
+    );
+
+    pub(crate) fn pipelineElementGetName(element: *const c_void, len: *mut usize) -> *const u8;
+
+    pub(crate) fn pipelineElementGetInnerPipeline(
+        element: *const c_void,
+        len: *mut usize,
+    ) -> *const c_void;
+
+    pub(crate) fn pipelineElementAt(elements: *const c_void, index: usize) -> *const c_void;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddLoopPipelineParsingCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(*const c_void, *const u8, usize, *mut c_void) -> bool,
+    );
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn passBuilderAddMachineFunctionPipelineParsingCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(*const c_void, *const u8, usize, *mut c_void) -> bool,
+    );
+
+    #[cfg(any(
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderSetInlineAdvisor(
+        builder: *mut c_void,
+        slot: *mut *mut c_void,
+        advisor: *const c_void,
+        advisor_deleter: extern "C" fn(*const c_void),
+        advisor_sys: extern "C" fn(
+            *const c_void,
+            *mut c_void,
+            *mut c_void,
+            *mut c_void,
+            *const crate::inline_advisor::InlineCostFFI,
+            *mut c_void,
+        ) -> bool,
+    );
+
+    #[cfg(any(
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn inlineAdviceSetReason(
+        reason: *mut c_void,
+        reason_ptr: *const u8,
+        reason_len: usize,
+    );
+
+    #[cfg(any(
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderSetInlineOrder(
+        builder: *mut c_void,
+        slot: *mut *mut c_void,
+        order: *const c_void,
+        order_deleter: extern "C" fn(*const c_void),
+        order_sys: extern "C" fn(
+            *const c_void,
+            *mut c_void,
+            *mut c_void,
+            *mut c_void,
+            *const crate::inline_advisor::InlineCostFFI,
+        ) -> i64,
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddShouldRunOptionalPassCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(
+            *const c_void,
+            *const u8,
+            usize,
+            crate::IrUnitKind,
+            *const c_void,
+        ) -> bool,
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddBeforePassCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(*const c_void, *const u8, usize, crate::IrUnitKind, *const c_void),
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddAfterPassCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(
+            *const c_void,
+            *const u8,
+            usize,
+            crate::IrUnitKind,
+            *const c_void,
+            crate::PreservedAnalyses,
+        ),
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddBeforeAnalysisCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(*const c_void, *const u8, usize, crate::IrUnitKind, *const c_void),
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddAfterAnalysisCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(*const c_void, *const u8, usize, crate::IrUnitKind, *const c_void),
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddAfterPassInvalidatedCallback(
+        builder: *mut c_void,
+        cb: *const c_void,
+        cb_deleter: extern "C" fn(*const c_void),
+        cb_sys: extern "C" fn(*const c_void, *const u8, usize, crate::PreservedAnalyses),
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn passBuilderAddClassToPassName(
+        builder: *mut c_void,
+        name_ptr: *const u8,
+        name_len: usize,
+    );
+
     pub(crate) fn modulePassManagerAddPass(
         manager: *mut c_void,
         pass: *mut c_void,
         pass_deleter: extern "C" fn(*mut c_void),
         pass_sys: extern "C" fn(*mut c_void, *mut c_void, *mut c_void) -> crate::PreservedAnalyses,
+        name_ptr: *const u8,
+        name_len: usize,
+        required: bool,
     );
 
     #[cfg(any(
@@ -203,11 +441,72 @@ extern "C" {
     ))]
     pub(crate) fn modulePassManagerIsEmpty(manager: *mut c_void) -> bool;
 
+    pub(crate) fn modulePassManagerAddFunctionPassManager(
+        manager: *mut c_void,
+        data: *mut c_void,
+        callback: extern "C" fn(*mut c_void, *mut c_void) -> bool,
+    );
+
+    pub(crate) fn modulePassManagerAddModulePassManager(
+        manager: *mut c_void,
+        data: *mut c_void,
+        callback: extern "C" fn(*mut c_void, *mut c_void) -> bool,
+    );
+
+    pub(crate) fn modulePassManagerAddPerModuleDefaultPipeline(
+        manager: *mut c_void,
+        builder: *mut c_void,
+        opt: crate::OptimizationLevel,
+    );
+
+    pub(crate) fn modulePassManagerAddModuleOptimizationPipeline(
+        manager: *mut c_void,
+        builder: *mut c_void,
+        opt: crate::OptimizationLevel,
+    );
+
+    pub(crate) fn modulePassManagerAddThinLTOPreLinkDefaultPipeline(
+        manager: *mut c_void,
+        builder: *mut c_void,
+        opt: crate::OptimizationLevel,
+    );
+
+    pub(crate) fn modulePassManagerAddLTOPreLinkDefaultPipeline(
+        manager: *mut c_void,
+        builder: *mut c_void,
+        opt: crate::OptimizationLevel,
+    );
+
+    pub(crate) fn functionPassManagerAddFunctionSimplificationPipeline(
+        manager: *mut c_void,
+        builder: *mut c_void,
+        opt: crate::OptimizationLevel,
+    );
+
+    pub(crate) fn modulePassManagerAddInvalidateAnalysisPass(manager: *mut c_void, id: AnalysisKey);
+
+    pub(crate) fn functionPassManagerAddInvalidateAnalysisPass(
+        manager: *mut c_void,
+        id: AnalysisKey,
+    );
+
+    pub(crate) fn modulePassManagerAddPipelineText(
+        manager: *mut c_void,
+        builder: *mut c_void,
+        text_ptr: *const u8,
+        text_len: usize,
+        error: *mut c_void,
+        on_error: extern "C" fn(*mut c_void, *const u8, usize),
+    ) -> bool;
+
     pub(crate) fn functionPassManagerAddPass(
         manager: *mut c_void,
         pass: *mut c_void,
         pass_deleter: extern "C" fn(*mut c_void),
         pass_sys: extern "C" fn(*mut c_void, *mut c_void, *mut c_void) -> crate::PreservedAnalyses,
+        name_ptr: *const u8,
+        name_len: usize,
+        required: bool,
     );
 
     #[cfg(any(
@@ -223,6 +522,15 @@ extern "C" {
     ))]
     pub(crate) fn functionPassManagerIsEmpty(manager: *mut c_void) -> bool;
 
+    pub(crate) fn functionPassManagerAddPipelineText(
+        manager: *mut c_void,
+        builder: *mut c_void,
+        text_ptr: *const u8,
+        text_len: usize,
+        error: *mut c_void,
+        on_error: extern "C" fn(*mut c_void, *const u8, usize),
+    ) -> bool;
+
     pub(crate) fn moduleAnalysisManagerRegisterPass(
         manager: *mut c_void,
         pass: *mut c_void,
@@ -235,6 +543,8 @@ extern "C" {
             res_deleter: *mut extern "C" fn(*mut c_void),
         ),
         id: AnalysisKey,
+        name_ptr: *const u8,
+        name_len: usize,
     ) -> bool;
 
     pub(crate) fn functionAnalysisManagerRegisterPass(
@@ -249,6 +559,8 @@ extern "C" {
             res_deleter: *mut extern "C" fn(*mut c_void),
         ),
         id: AnalysisKey,
+        name_ptr: *const u8,
+        name_len: usize,
     ) -> bool;
 
     fn getFunctionAnalysisManagerModuleProxy(
@@ -282,6 +594,366 @@ extern "C" {
         module: *mut c_void,
     ) -> *mut c_void;
 
+    pub(crate) fn getOptimizationRemarkEmitter(
+        manager: *mut c_void,
+        function: *mut c_void,
+    ) -> *mut c_void;
+
+    pub(crate) fn optimizationRemarkEmitterIsEnabled(emitter: *mut c_void) -> bool;
+
+    pub(crate) fn optimizationRemarkEmitterEmit(
+        emitter: *mut c_void,
+        function: *mut c_void,
+        kind: crate::RemarkKind,
+        pass_name_ptr: *const u8,
+        pass_name_len: usize,
+        remark_name_ptr: *const u8,
+        remark_name_len: usize,
+        inst: *mut c_void,
+        args: *const crate::RemarkArg,
+        args_len: usize,
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopPassManagerAddLoopNestPass(
+        manager: *mut c_void,
+        pass: *mut c_void,
+        pass_deleter: extern "C" fn(*mut c_void),
+        pass_sys: extern "C" fn(*mut c_void, *mut c_void) -> crate::PreservedAnalyses,
+        name_ptr: *const u8,
+        name_len: usize,
+        required: bool,
+    );
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopNestGetNumLoops(loop_nest: *mut c_void) -> usize;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopNestGetLoop(loop_nest: *mut c_void, index: usize) -> *mut c_void;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopNestGetNestDepth(loop_nest: *mut c_void) -> u32;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopNestGetMaxPerfectDepth(loop_nest: *mut c_void) -> u32;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopNestGetFunction(loop_nest: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopGetDepth(loop_: *mut c_void) -> u32;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopGetHeader(loop_: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopGetNumBlocks(loop_: *mut c_void) -> usize;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopGetBlock(loop_: *mut c_void, index: usize) -> *mut c_void;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopGetNumSubLoops(loop_: *mut c_void) -> usize;
+
+    #[cfg(any(
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub(crate) fn loopGetSubLoop(loop_: *mut c_void, index: usize) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineFunctionPassManagerAddPass(
+        manager: *mut c_void,
+        pass: *mut c_void,
+        pass_deleter: extern "C" fn(*mut c_void),
+        pass_sys: extern "C" fn(*mut c_void, *mut c_void, *mut c_void) -> crate::PreservedAnalyses,
+        name_ptr: *const u8,
+        name_len: usize,
+        required: bool,
+    );
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineFunctionGetContext(function: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineFunctionGetName(function: *mut c_void) -> *const std::ffi::c_char;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineFunctionGetFunction(function: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineFunctionGetFirstBlock(function: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineBasicBlockGetNext(block: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineBasicBlockGetNumber(block: *mut c_void) -> i32;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineBasicBlockGetName(block: *mut c_void) -> *const std::ffi::c_char;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineBasicBlockGetFirstInstr(block: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrGetNext(instr: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrGetParent(instr: *mut c_void) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrGetOpcode(instr: *mut c_void) -> u32;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrGetOpcodeName(instr: *mut c_void, len: *mut usize) -> *const u8;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrIsTerminator(instr: *mut c_void) -> bool;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrIsCall(instr: *mut c_void) -> bool;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrGetNumOperands(instr: *mut c_void) -> u32;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineInstrGetOperand(instr: *mut c_void, index: u32) -> *mut c_void;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineOperandGetKind(
+        operand: *mut c_void,
+    ) -> crate::machine_function::MachineOperandKind;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineOperandGetReg(operand: *mut c_void) -> u32;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineOperandIsDef(operand: *mut c_void) -> bool;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineOperandGetImm(operand: *mut c_void) -> i64;
+
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    pub(crate) fn machineOperandGetBlock(operand: *mut c_void) -> *mut c_void;
+
+    pub(crate) fn moduleGetContext(module: *mut c_void) -> *mut c_void;
+
+    pub(crate) fn functionGetContext(function: *mut c_void) -> *mut c_void;
+
+    pub(crate) fn llvmContextDiagnose(
+        context: *mut c_void,
+        module: *mut c_void,
+        function: *mut c_void,
+        severity: crate::DiagnosticSeverity,
+        msg_ptr: *const u8,
+        msg_len: usize,
+        file_ptr: *const u8,
+        file_len: usize,
+        line: u32,
+        column: u32,
+    );
+
+    pub(crate) fn instructionGetDebugLoc(
+        inst: *mut c_void,
+        dir_ptr: *mut *const u8,
+        dir_len: *mut usize,
+        file_ptr: *mut *const u8,
+        file_len: *mut usize,
+        line: *mut u32,
+        column: *mut u32,
+    ) -> bool;
+
+    pub(crate) fn llvmErrsWrite(ptr: *const u8, len: usize);
+
+    pub(crate) fn reportFatalError(msg_ptr: *const u8, msg_len: usize) -> !;
+
+    pub(crate) fn debugCounterRegister(
+        name_ptr: *const u8,
+        name_len: usize,
+        desc_ptr: *const u8,
+        desc_len: usize,
+    ) -> u32;
+
+    pub(crate) fn debugCounterShouldExecute(id: u32) -> bool;
+
+    pub(crate) fn statisticNew(
+        debug_type_ptr: *const u8,
+        debug_type_len: usize,
+        name_ptr: *const u8,
+        name_len: usize,
+        desc_ptr: *const u8,
+        desc_len: usize,
+    ) -> *mut c_void;
+
+    pub(crate) fn statisticAdd(stat: *mut c_void, value: u32);
+
+    pub(crate) fn statisticUpdateMax(stat: *mut c_void, value: u32);
+
+    pub(crate) fn statisticGetValue(stat: *mut c_void) -> u32;
+
+    pub(crate) fn timeTraceProfilerIsEnabled() -> bool;
+
+    pub(crate) fn timeTraceProfilerBegin(
+        name_ptr: *const u8,
+        name_len: usize,
+        detail_ptr: *const u8,
+        detail_len: usize,
+    );
+
+    pub(crate) fn timeTraceProfilerEnd();
+
+    pub(crate) fn passBuilderGetTargetMachine(builder: *mut c_void) -> *mut c_void;
+
+    pub(crate) fn passBuilderGetPipelineTuningOptions(
+        builder: *mut c_void,
+    ) -> crate::PipelineTuningOptions;
+
+    pub(crate) fn passBuilderGetPGOOptions(builder: *mut c_void) -> *mut c_void;
+
+    pub(crate) fn pgoOptionsGetAction(pgo_options: *mut c_void) -> crate::PGOAction;
+
+    pub(crate) fn pgoOptionsGetCSAction(pgo_options: *mut c_void) -> crate::CSPGOAction;
+
+    pub(crate) fn pgoOptionsGetProfileFile(pgo_options: *mut c_void, len: *mut usize) -> *const u8;
+
+    pub(crate) fn targetMachineGetTriple(target_machine: *mut c_void, len: *mut usize)
+        -> *const u8;
+
+    pub(crate) fn targetMachineGetCPU(target_machine: *mut c_void, len: *mut usize) -> *const u8;
+
+    pub(crate) fn targetMachineGetFeatures(
+        target_machine: *mut c_void,
+        len: *mut usize,
+    ) -> *const u8;
+
+    pub(crate) fn targetMachineGetCodeModel(target_machine: *mut c_void) -> crate::CodeModel;
+
+    pub(crate) fn targetMachineGetDataLayout(
+        target_machine: *mut c_void,
+        data: *mut c_void,
+        write: extern "C" fn(*mut c_void, *const u8, usize),
+    );
+
+    pub(crate) fn setPluginName(name_ptr: *const u8, name_len: usize);
+
     fn llvmPluginApiVersion() -> u32;
 }
 
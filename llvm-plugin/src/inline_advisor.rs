@@ -0,0 +1,161 @@
+use inkwell::values::{FunctionValue, InstructionValue};
+
+/// Trait to use for implementing an inline advisor.
+///
+/// An inline advisor decides, for each call site considered by the
+/// inliner, whether the callee should be inlined into its caller. It
+/// replaces the default cost-based heuristic of LLVM, and is installed
+/// with [PassBuilder::set_inline_advisor](crate::PassBuilder::set_inline_advisor).
+///
+/// Mandatory decisions are taken without consulting the advisor: call
+/// sites to `alwaysinline` functions are inlined, while call sites to
+/// `noinline` functions and recursive calls are not.
+pub trait LlvmInlineAdvisor {
+    /// Entrypoint for the advisor.
+    ///
+    /// The advice returned by this function is followed by the inliner,
+    /// unless inlining the call site turns out not to be legal. It is
+    /// only requested for call sites without a mandatory decision.
+    fn advise(&self, call_site: &InlineCallSite<'_>) -> InlineAdvice;
+}
+
+/// Call site considered for inlining.
+pub struct InlineCallSite<'a> {
+    caller: FunctionValue<'a>,
+    callee: FunctionValue<'a>,
+    call: InstructionValue<'a>,
+    default_cost: InlineCost,
+}
+
+impl<'a> InlineCallSite<'a> {
+    pub(crate) unsafe fn from_raw(
+        caller: *mut std::ffi::c_void,
+        callee: *mut std::ffi::c_void,
+        call: *mut std::ffi::c_void,
+        cost: *const InlineCostFFI,
+    ) -> Self {
+        let cost = &*cost;
+        Self {
+            caller: FunctionValue::new(caller.cast()).unwrap(),
+            callee: FunctionValue::new(callee.cast()).unwrap(),
+            call: InstructionValue::new(call.cast()),
+            default_cost: match cost.kind {
+                InlineCostKind::Variable => InlineCost::Variable {
+                    cost: cost.cost,
+                    threshold: cost.threshold,
+                },
+                InlineCostKind::Always => InlineCost::Always,
+                InlineCostKind::Never => InlineCost::Never,
+            },
+        }
+    }
+
+    /// Get the function containing the call.
+    pub fn caller(&self) -> FunctionValue<'a> {
+        self.caller
+    }
+
+    /// Get the function being called.
+    pub fn callee(&self) -> FunctionValue<'a> {
+        self.callee
+    }
+
+    /// Get the call instruction.
+    pub fn call(&self) -> InstructionValue<'a> {
+        self.call
+    }
+
+    /// Get the cost computed by the default inline advisor of LLVM.
+    pub fn default_cost(&self) -> InlineCost {
+        self.default_cost
+    }
+}
+
+/// Cost of inlining a call site, as computed by LLVM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InlineCost {
+    /// The call site should always be inlined.
+    Always,
+
+    /// The call site should never be inlined.
+    Never,
+
+    /// The call site should be inlined if its cost is below the threshold.
+    Variable {
+        /// Estimated cost of inlining the call site.
+        cost: i32,
+
+        /// Threshold above which inlining is not profitable.
+        threshold: i32,
+    },
+}
+
+impl InlineCost {
+    /// Whether inlining the call site is profitable.
+    pub fn is_profitable(&self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Variable { cost, threshold } => cost < threshold,
+        }
+    }
+}
+
+/// Decision of an inline advisor for a call site.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineAdvice {
+    inline: bool,
+    reason: Option<String>,
+}
+
+impl InlineAdvice {
+    /// Advise to inline the call site.
+    pub fn inline() -> Self {
+        Self {
+            inline: true,
+            reason: None,
+        }
+    }
+
+    /// Advise not to inline the call site.
+    pub fn no_inline() -> Self {
+        Self {
+            inline: false,
+            reason: None,
+        }
+    }
+
+    /// Attach a reason to this advice.
+    ///
+    /// The reason is reported in the optimization remarks of the inliner.
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// Whether inlining the call site is recommended.
+    pub fn is_inlining_recommended(&self) -> bool {
+        self.inline
+    }
+
+    /// Get the reason of this advice, if any.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub(crate) enum InlineCostKind {
+    Variable,
+    Always,
+    Never,
+}
+
+#[repr(C)]
+pub(crate) struct InlineCostFFI {
+    kind: InlineCostKind,
+    cost: i32,
+    threshold: i32,
+}
@@ -0,0 +1,21 @@
+use crate::InlineCallSite;
+
+/// Trait to use for implementing an inline order.
+///
+/// An inline order assigns priorities to the call sites considered by the
+/// module inliner (the `module-inline` pass, used by default pipelines
+/// when `-enable-module-inliner` is given), which visits call sites with
+/// higher priorities first. It replaces the priority
+/// heuristic selected by `-inline-priority-mode`, and is installed with
+/// [PassBuilder::set_inline_order](crate::PassBuilder::set_inline_order).
+///
+/// Whether a call site actually gets inlined is still decided by the
+/// inline advisor.
+pub trait LlvmInlineOrder {
+    /// Entrypoint for the inline order.
+    ///
+    /// The priority of a call site is computed when it is pushed to the
+    /// queue of the inliner, and computed again before it is popped, since
+    /// inlining other call sites may have changed it.
+    fn priority(&self, call_site: &InlineCallSite<'_>) -> i64;
+}
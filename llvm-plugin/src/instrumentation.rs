@@ -0,0 +1,48 @@
+use std::ffi::c_void;
+
+use crate::{FunctionRef, ModuleRef};
+
+#[doc(hidden)]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrUnitKind {
+    Module,
+    Function,
+    Loop,
+    CallGraphScc,
+    Other,
+}
+
+/// IR unit a pass or an analysis is running on, as given to pass
+/// instrumentation callbacks.
+#[non_exhaustive]
+pub enum IrUnit<'a> {
+    /// A module.
+    Module(ModuleRef<'a>),
+
+    /// A function.
+    Function(FunctionRef<'a>),
+
+    /// A loop, given along with its parent function.
+    Loop(FunctionRef<'a>),
+
+    /// A strongly connected component of the call graph.
+    CallGraphScc,
+
+    /// An IR unit not supported by this crate.
+    Other,
+}
+
+impl IrUnit<'_> {
+    pub(crate) unsafe fn from_raw(kind: IrUnitKind, unit: *const c_void) -> Self {
+        match kind {
+            IrUnitKind::Module => Self::Module(unsafe { ModuleRef::from_raw(unit.cast_mut()) }),
+            IrUnitKind::Function => {
+                Self::Function(unsafe { FunctionRef::from_raw(unit.cast_mut()) })
+            }
+            IrUnitKind::Loop => Self::Loop(unsafe { FunctionRef::from_raw(unit.cast_mut()) }),
+            IrUnitKind::CallGraphScc => Self::CallGraphScc,
+            IrUnitKind::Other => Self::Other,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::ffi::c_void;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+
+use inkwell::module::Module;
+use inkwell::values::{AsValueRef, FunctionValue};
+
+use crate::Diagnostics;
+
+/// Non-owning handle to an LLVM module.
+///
+/// The module given to passes and analyses is owned by the LLVM tool
+/// running them. This handle gives access to the [Module] API through
+/// [Deref], but never disposes of the underlying module, even when unwinding.
+///
+/// Mutable access to the inner [Module] is purposely not provided, since
+/// it would allow moving it out of this handle (e.g. with [std::mem::replace])
+/// and disposing of it.
+#[derive(Debug)]
+pub struct ModuleRef<'a> {
+    inner: ManuallyDrop<Module<'a>>,
+}
+
+impl ModuleRef<'_> {
+    #[doc(hidden)]
+    pub unsafe fn from_raw(module: *mut c_void) -> Self {
+        Self {
+            inner: ManuallyDrop::new(Module::new(module.cast())),
+        }
+    }
+
+    /// Returns a handle allowing to emit diagnostics through the
+    /// `LLVMContext` of this module.
+    pub fn diagnostics(&self) -> Diagnostics<'_> {
+        unsafe { Diagnostics::from_module(self.as_mut_ptr().cast()) }
+    }
+}
+
+impl<'a> Deref for ModuleRef<'a> {
+    type Target = Module<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/// Non-owning handle to an LLVM function.
+///
+/// The function given to passes and analyses is owned by its parent module.
+/// This handle gives access to the [FunctionValue] API through [Deref].
+#[derive(Debug)]
+pub struct FunctionRef<'a> {
+    inner: FunctionValue<'a>,
+}
+
+impl FunctionRef<'_> {
+    #[doc(hidden)]
+    pub unsafe fn from_raw(function: *mut c_void) -> Self {
+        Self {
+            inner: FunctionValue::new(function.cast()).unwrap(),
+        }
+    }
+
+    /// Returns a handle allowing to emit diagnostics through the
+    /// `LLVMContext` of this function.
+    pub fn diagnostics(&self) -> Diagnostics<'_> {
+        let function = self.as_value_ref().cast();
+        unsafe { Diagnostics::from_function(function) }
+    }
+}
+
+impl<'a> Deref for FunctionRef<'a> {
+    type Target = FunctionValue<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
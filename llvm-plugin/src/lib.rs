@@ -33,9 +33,9 @@
 //! The following code illustrates the idea:
 //!
 //! ```no_run
-//! # use llvm_plugin::inkwell::module::Module;
 //! # use llvm_plugin::{
-//! #     LlvmModulePass, ModuleAnalysisManager, PassBuilder, PipelineParsing, PreservedAnalyses,
+//! #     LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+//! #     PreservedAnalyses,
 //! # };
 //! // A name and version is required.
 //! #[llvm_plugin::plugin(name = "plugin_name", version = "0.1")]
@@ -63,7 +63,7 @@
 //! impl LlvmModulePass for CustomPass {
 //!     fn run_pass(
 //!         &self,
-//!         module: &mut Module,
+//!         module: &mut ModuleRef,
 //!         manager: &ModuleAnalysisManager
 //!     ) -> PreservedAnalyses {
 //!         // transform the IR
@@ -102,14 +102,118 @@
 mod analysis;
 pub use analysis::*;
 
+mod analysis_pipeline;
+
+mod debug_counter;
+pub use debug_counter::*;
+
+mod diagnostic;
+pub use diagnostic::*;
+
 #[doc(hidden)]
 pub mod ffi;
 #[doc(hidden)]
 pub use ffi::*;
 
 pub use inkwell;
-use inkwell::module::Module;
-use inkwell::values::FunctionValue;
+
+#[cfg(any(
+    doc,
+    feature = "llvm16-0",
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+mod inline_advisor;
+#[cfg(any(
+    doc,
+    feature = "llvm16-0",
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+pub use inline_advisor::*;
+
+#[cfg(any(
+    doc,
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+mod inline_order;
+#[cfg(any(
+    doc,
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+pub use inline_order::*;
+
+#[cfg(any(
+    doc,
+    feature = "llvm12-0",
+    feature = "llvm13-0",
+    feature = "llvm14-0",
+    feature = "llvm15-0",
+    feature = "llvm16-0",
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+mod instrumentation;
+#[cfg(any(
+    doc,
+    feature = "llvm12-0",
+    feature = "llvm13-0",
+    feature = "llvm14-0",
+    feature = "llvm15-0",
+    feature = "llvm16-0",
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+pub use instrumentation::*;
+
+mod ir;
+pub use ir::*;
+
+#[cfg(any(
+    doc,
+    feature = "llvm12-0",
+    feature = "llvm13-0",
+    feature = "llvm14-0",
+    feature = "llvm15-0",
+    feature = "llvm16-0",
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+mod loop_nest;
+#[cfg(any(
+    doc,
+    feature = "llvm12-0",
+    feature = "llvm13-0",
+    feature = "llvm14-0",
+    feature = "llvm15-0",
+    feature = "llvm16-0",
+    feature = "llvm17-0",
+    feature = "llvm18-1",
+    feature = "llvm19-1",
+    feature = "llvm20-1",
+))]
+pub use loop_nest::*;
+
+#[cfg(any(doc, feature = "llvm19-1", feature = "llvm20-1"))]
+mod machine_function;
+#[cfg(any(doc, feature = "llvm19-1", feature = "llvm20-1"))]
+pub use machine_function::*;
 
 mod pass_manager;
 pub use pass_manager::*;
@@ -117,6 +221,23 @@ pub use pass_manager::*;
 mod pass_builder;
 pub use pass_builder::*;
 
+mod pipeline_options;
+pub use pipeline_options::*;
+
+mod remark;
+pub use remark::*;
+
+mod statistic;
+pub use statistic::*;
+
+mod target_machine;
+pub use target_machine::*;
+
+mod time_trace;
+pub use time_trace::*;
+
+mod unwind;
+
 /// Enum specifying whether analyses on an IR unit are not preserved due
 /// to the modification of such unit by a transformation pass.
 #[repr(C)]
@@ -139,6 +260,10 @@ pub enum PreservedAnalyses {
 /// Trait to use for implementing a transformation pass on an LLVM module.
 ///
 /// A transformation pass is allowed to mutate the LLVM IR.
+///
+/// Because of its associated constants, this trait is not dyn-compatible,
+/// i.e. `Box<dyn LlvmModulePass>` does not compile. Passes chosen at
+/// runtime can be wrapped in an enum implementing this trait instead.
 pub trait LlvmModulePass {
     /// Entrypoint for the pass.
     ///
@@ -150,14 +275,50 @@ pub trait LlvmModulePass {
     /// pass manager that all analyses are now invalidated.
     fn run_pass(
         &self,
-        module: &mut Module<'_>,
+        module: &mut ModuleRef<'_>,
         manager: &ModuleAnalysisManager,
     ) -> PreservedAnalyses;
+
+    /// Name of the pass, as reported to LLVM tooling.
+    ///
+    /// This name shows up in the output of options such as
+    /// `-debug-pass-manager` or `-time-passes`, and can be given to options
+    /// such as `-print-after=<name>`. It defaults to the Rust type name of
+    /// the pass.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Whether a panic in [run_pass](Self::run_pass) should abort the
+    /// LLVM tool running this pass.
+    ///
+    /// By default, a panic is reported as an LLVM fatal error including the
+    /// plugin name, the pass type and the panic message.
+    ///
+    /// Passes which only inspect the IR (e.g. printers) may set this to
+    /// `false`. The panic is then reported as a warning, and the pipeline
+    /// keeps running. Since the pass may have stopped in the middle of a
+    /// modification, all analyses are invalidated.
+    ///
+    /// Analyses have no such option: a panicking analysis has no result to
+    /// give to the passes requesting it, so its panic is always reported
+    /// as a fatal error.
+    const ABORT_ON_PANIC: bool = true;
+
+    /// Whether this pass must run, even when LLVM would skip optional passes.
+    ///
+    /// LLVM skips optional passes on functions marked `optnone` (e.g. at
+    /// `-O0`), or past the limit given to `-opt-bisect-limit`. Passes which
+    /// must run regardless (e.g. mandatory instrumentation) should set this
+    /// to `true`.
+    const REQUIRED: bool = false;
 }
 
 /// Trait to use for implementing a transformation pass on an LLVM function.
 ///
 /// A transformation pass is allowed to mutate the LLVM IR.
+///
+/// Like [LlvmModulePass], this trait cannot be used as a trait object.
 pub trait LlvmFunctionPass {
     /// Entrypoint for the pass.
     ///
@@ -169,9 +330,163 @@ pub trait LlvmFunctionPass {
     /// pass manager that all analyses are now invalidated.
     fn run_pass(
         &self,
-        function: &mut FunctionValue<'_>,
+        function: &mut FunctionRef<'_>,
         manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses;
+
+    /// Name of the pass, as reported to LLVM tooling.
+    ///
+    /// This name shows up in the output of options such as
+    /// `-debug-pass-manager` or `-time-passes`, and can be given to options
+    /// such as `-print-after=<name>`. It defaults to the Rust type name of
+    /// the pass.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Whether a panic in [run_pass](Self::run_pass) should abort the
+    /// LLVM tool running this pass.
+    ///
+    /// By default, a panic is reported as an LLVM fatal error including the
+    /// plugin name, the pass type and the panic message.
+    ///
+    /// Passes which only inspect the IR (e.g. printers) may set this to
+    /// `false`. The panic is then reported as a warning, and the pipeline
+    /// keeps running. Since the pass may have stopped in the middle of a
+    /// modification, all analyses are invalidated.
+    ///
+    /// Analyses have no such option: a panicking analysis has no result to
+    /// give to the passes requesting it, so its panic is always reported
+    /// as a fatal error.
+    const ABORT_ON_PANIC: bool = true;
+
+    /// Whether this pass must run, even when LLVM would skip optional passes.
+    ///
+    /// LLVM skips optional passes on functions marked `optnone` (e.g. at
+    /// `-O0`), or past the limit given to `-opt-bisect-limit`. Passes which
+    /// must run regardless (e.g. mandatory instrumentation) should set this
+    /// to `true`.
+    const REQUIRED: bool = false;
+}
+
+/// Trait to use for implementing a fallible transformation pass on an
+/// LLVM module.
+///
+/// Any type implementing this trait also implements [LlvmModulePass], and can
+/// therefore be added to a [ModulePassManager].
+pub trait LlvmFallibleModulePass {
+    /// Entrypoint for the pass.
+    ///
+    /// The given analysis manager allows the pass to query the pass
+    /// manager for the result of specific analysis passes.
+    ///
+    /// If this function makes modifications on the given module IR, it
+    /// should return `Ok(PreservedAnalyses::None)` to indicate to the
+    /// pass manager that all analyses are now invalidated.
+    ///
+    /// A returned [PassError] is reported as a diagnostic through the
+    /// module's `LLVMContext`, and all analyses are then considered
+    /// invalidated.
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef<'_>,
+        manager: &ModuleAnalysisManager,
+    ) -> Result<PreservedAnalyses, PassError>;
+
+    /// See [LlvmModulePass::name].
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// See [LlvmModulePass::ABORT_ON_PANIC].
+    const ABORT_ON_PANIC: bool = true;
+
+    /// See [LlvmModulePass::REQUIRED].
+    const REQUIRED: bool = false;
+}
+
+impl<T> LlvmModulePass for T
+where
+    T: LlvmFallibleModulePass,
+{
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef<'_>,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        LlvmFallibleModulePass::run_pass(self, module, manager).unwrap_or_else(|err| {
+            err.emit(&module.diagnostics());
+            PreservedAnalyses::None
+        })
+    }
+
+    fn name(&self) -> &str {
+        LlvmFallibleModulePass::name(self)
+    }
+
+    const ABORT_ON_PANIC: bool = <T as LlvmFallibleModulePass>::ABORT_ON_PANIC;
+
+    const REQUIRED: bool = <T as LlvmFallibleModulePass>::REQUIRED;
+}
+
+/// Trait to use for implementing a fallible transformation pass on an
+/// LLVM function.
+///
+/// Any type implementing this trait also implements [LlvmFunctionPass], and
+/// can therefore be added to a [FunctionPassManager].
+pub trait LlvmFallibleFunctionPass {
+    /// Entrypoint for the pass.
+    ///
+    /// The given analysis manager allows the pass to query the pass
+    /// manager for the result of specific analysis passes.
+    ///
+    /// If this function makes modifications on the given function IR, it
+    /// should return `Ok(PreservedAnalyses::None)` to indicate to the
+    /// pass manager that all analyses are now invalidated.
+    ///
+    /// A returned [PassError] is reported as a diagnostic through the
+    /// function's `LLVMContext`, and all analyses are then considered
+    /// invalidated.
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef<'_>,
+        manager: &FunctionAnalysisManager,
+    ) -> Result<PreservedAnalyses, PassError>;
+
+    /// See [LlvmFunctionPass::name].
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// See [LlvmFunctionPass::ABORT_ON_PANIC].
+    const ABORT_ON_PANIC: bool = true;
+
+    /// See [LlvmFunctionPass::REQUIRED].
+    const REQUIRED: bool = false;
+}
+
+impl<T> LlvmFunctionPass for T
+where
+    T: LlvmFallibleFunctionPass,
+{
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef<'_>,
+        manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        LlvmFallibleFunctionPass::run_pass(self, function, manager).unwrap_or_else(|err| {
+            err.emit(&function.diagnostics());
+            PreservedAnalyses::None
+        })
+    }
+
+    fn name(&self) -> &str {
+        LlvmFallibleFunctionPass::name(self)
+    }
+
+    const ABORT_ON_PANIC: bool = <T as LlvmFallibleFunctionPass>::ABORT_ON_PANIC;
+
+    const REQUIRED: bool = <T as LlvmFallibleFunctionPass>::REQUIRED;
 }
 
 /// Trait to use for implementing an analysis pass on an LLVM module.
@@ -191,7 +506,8 @@ pub trait LlvmModuleAnalysis {
     /// The returned result will be moved into a [Box](`std::boxed::Box`)
     /// before being given to the pass manager. This one will then add it to
     /// its internal cache, to avoid unnecessary calls to this entrypoint.
-    fn run_analysis(&self, module: &Module<'_>, manager: &ModuleAnalysisManager) -> Self::Result;
+    fn run_analysis(&self, module: &ModuleRef<'_>, manager: &ModuleAnalysisManager)
+        -> Self::Result;
 
     /// Identifier for the analysis type.
     ///
@@ -210,14 +526,13 @@ pub trait LlvmModuleAnalysis {
     /// # Example
     ///
     /// ```
-    /// # use llvm_plugin::inkwell::module::Module;
-    /// # use llvm_plugin::{AnalysisKey, LlvmModuleAnalysis, ModuleAnalysisManager};
+    /// # use llvm_plugin::{AnalysisKey, LlvmModuleAnalysis, ModuleAnalysisManager, ModuleRef};
     /// # struct Analysis;
     /// # impl LlvmModuleAnalysis for Analysis {
     /// #    type Result = ();
     /// #    fn run_analysis(
     /// #        &self,
-    /// #        _module: &Module,
+    /// #        _module: &ModuleRef,
     /// #        _manager: &ModuleAnalysisManager,
     /// #    ) -> Self::Result {}
     /// #
@@ -231,6 +546,15 @@ pub trait LlvmModuleAnalysis {
     /// [opt]: https://www.llvm.org/docs/CommandGuide/opt.html
     /// [lld]: https://lld.llvm.org/
     fn id() -> AnalysisKey;
+
+    /// Name of this analysis in textual pipelines, if any.
+    ///
+    /// Once the analysis is registered, the `require<name>` and
+    /// `invalidate<name>` pipeline elements respectively compute and
+    /// invalidate its result. The `print<name>` element is also available
+    /// if the analysis was registered with
+    /// [ModuleAnalysisManager::register_printable_pass].
+    const PIPELINE_NAME: Option<&'static str> = None;
 }
 
 /// Trait to use for implementing an analysis pass on an LLVM function.
@@ -252,7 +576,7 @@ pub trait LlvmFunctionAnalysis {
     /// its internal cache, to avoid unnecessary calls to this entrypoint.
     fn run_analysis(
         &self,
-        module: &FunctionValue<'_>,
+        function: &FunctionRef<'_>,
         manager: &FunctionAnalysisManager,
     ) -> Self::Result;
 
@@ -273,14 +597,13 @@ pub trait LlvmFunctionAnalysis {
     /// # Example
     ///
     /// ```
-    /// # use llvm_plugin::inkwell::values::FunctionValue;
-    /// # use llvm_plugin::{AnalysisKey, LlvmFunctionAnalysis, FunctionAnalysisManager};
+    /// # use llvm_plugin::{AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis};
     /// # struct Analysis;
     /// # impl LlvmFunctionAnalysis for Analysis {
     /// #    type Result = ();
     /// #    fn run_analysis(
     /// #        &self,
-    /// #        _function: &FunctionValue,
+    /// #        _function: &FunctionRef,
     /// #        _manager: &FunctionAnalysisManager,
     /// #    ) -> Self::Result {}
     /// #
@@ -294,6 +617,34 @@ pub trait LlvmFunctionAnalysis {
     /// [opt]: https://www.llvm.org/docs/CommandGuide/opt.html
     /// [lld]: https://lld.llvm.org/
     fn id() -> AnalysisKey;
+
+    /// Name of this analysis in textual pipelines, if any.
+    ///
+    /// Once the analysis is registered, the `require<name>` and
+    /// `invalidate<name>` pipeline elements respectively compute and
+    /// invalidate its result. The `print<name>` element is also available
+    /// if the analysis was registered with
+    /// [FunctionAnalysisManager::register_printable_pass].
+    const PIPELINE_NAME: Option<&'static str> = None;
+}
+
+#[doc(hidden)]
+pub unsafe fn run_plugin_registrar__<T>(
+    plugin_name: &'static str,
+    registrar_name: &str,
+    builder: *mut std::ffi::c_void,
+    registrar: T,
+) where
+    T: FnOnce(&mut PassBuilder),
+{
+    unwind::set_plugin_name(plugin_name);
+    unsafe { ffi::setPluginName(plugin_name.as_ptr(), plugin_name.len()) };
+    let mut builder = unsafe { PassBuilder::from_raw(builder) };
+    unwind::catch_unwind("registrar", registrar_name, || registrar(&mut builder));
+
+    builder.add_module_pipeline_parsing_callback(analysis_pipeline::parse_module_analysis_element);
+    builder
+        .add_function_pipeline_parsing_callback(analysis_pipeline::parse_function_analysis_element);
 }
 
 #[doc(hidden)]
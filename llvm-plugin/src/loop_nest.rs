@@ -0,0 +1,231 @@
+use std::any::type_name;
+use std::ffi::c_void;
+use std::marker::PhantomData;
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::values::FunctionValue;
+
+use crate::PreservedAnalyses;
+
+/// Trait to use for implementing a pass on an LLVM loop nest.
+///
+/// Loop nest passes run in the loop pipeline (e.g. `function(loop(<name>))`
+/// with opt), once per outermost loop of each function, and are registered
+/// with [PassBuilder::add_loop_pipeline_parsing_callback].
+///
+/// Even when the pass modifies the IR, the loop pass manager keeps the
+/// standard loop analyses (e.g. the loop info, the dominator tree, scalar
+/// evolution) alive. A pass returning `PreservedAnalyses::None` must
+/// therefore keep them up-to-date, which in practice means it should not
+/// change the control flow of the function.
+///
+/// [PassBuilder::add_loop_pipeline_parsing_callback]: crate::PassBuilder::add_loop_pipeline_parsing_callback
+pub trait LlvmLoopNestPass {
+    /// Entrypoint for the pass.
+    ///
+    /// If this function makes modifications on the given loop nest, it
+    /// should return `PreservedAnalyses::None` to indicate to the pass
+    /// manager that all analyses, but the standard loop ones, are now
+    /// invalidated.
+    fn run_pass(&self, loop_nest: &mut LoopNest<'_>) -> PreservedAnalyses;
+
+    /// Name of the pass, as reported to LLVM tooling.
+    ///
+    /// See [LlvmModulePass::name](crate::LlvmModulePass::name).
+    fn name(&self) -> &str {
+        type_name::<Self>()
+    }
+
+    /// Whether a panic in [run_pass](Self::run_pass) should abort the
+    /// LLVM tool running this pass.
+    ///
+    /// See [LlvmModulePass::ABORT_ON_PANIC](crate::LlvmModulePass::ABORT_ON_PANIC).
+    const ABORT_ON_PANIC: bool = true;
+
+    /// Whether this pass must run, even when LLVM would skip optional passes.
+    ///
+    /// See [LlvmModulePass::REQUIRED](crate::LlvmModulePass::REQUIRED).
+    const REQUIRED: bool = false;
+}
+
+/// Struct allowing to add passes on LLVM loop nests to the pass manager
+/// pipeline.
+pub struct LoopPassManager {
+    inner: *mut c_void,
+    builder: *mut c_void,
+}
+
+impl LoopPassManager {
+    #[doc(hidden)]
+    pub unsafe fn from_raw(pass_manager: *mut c_void, builder: *mut c_void) -> Self {
+        Self {
+            inner: pass_manager,
+            builder,
+        }
+    }
+
+    /// Adds a loop nest pass to this pass manager.
+    ///
+    /// The pass is reported to LLVM tooling under the name returned by
+    /// [LlvmLoopNestPass::name].
+    pub fn add_loop_nest_pass<T>(&mut self, pass: T)
+    where
+        T: LlvmLoopNestPass,
+    {
+        let name = pass.name().to_owned();
+        let pass = Box::new(pass);
+
+        extern "C" fn pass_deleter<T>(pass: *mut c_void) {
+            crate::unwind::catch_unwind("loop nest pass", type_name::<T>(), || {
+                drop(unsafe { Box::<T>::from_raw(pass.cast()) })
+            })
+        }
+
+        extern "C" fn pass_entrypoint<T>(
+            pass: *mut c_void,
+            loop_nest: *mut c_void,
+        ) -> PreservedAnalyses
+        where
+            T: LlvmLoopNestPass,
+        {
+            let pass = unsafe { &*pass.cast::<T>() };
+            let context =
+                unsafe { super::functionGetContext(super::loopNestGetFunction(loop_nest)) };
+
+            crate::unwind::catch_pass_unwind(
+                "loop nest pass",
+                type_name::<T>(),
+                T::ABORT_ON_PANIC,
+                context,
+                || {
+                    let mut loop_nest = unsafe { LoopNest::from_raw(loop_nest) };
+                    pass.run_pass(&mut loop_nest)
+                },
+            )
+        }
+
+        unsafe {
+            super::loopPassManagerAddLoopNestPass(
+                self.inner,
+                Box::into_raw(pass).cast(),
+                pass_deleter::<T>,
+                pass_entrypoint::<T>,
+                name.as_ptr(),
+                name.len(),
+                T::REQUIRED,
+            )
+        }
+
+        if !self.builder.is_null() {
+            unsafe { super::passBuilderAddClassToPassName(self.builder, name.as_ptr(), name.len()) }
+        }
+    }
+}
+
+/// View of an LLVM loop nest, i.e. an outermost loop along with all the
+/// loops it contains.
+pub struct LoopNest<'a> {
+    inner: *mut c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> LoopNest<'a> {
+    unsafe fn from_raw(loop_nest: *mut c_void) -> Self {
+        Self {
+            inner: loop_nest,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the loops of this nest, from the outermost to the innermost.
+    ///
+    /// Loops are listed breadth-first, so that a loop always comes before
+    /// its sub-loops.
+    pub fn loops(&self) -> Vec<Loop<'a>> {
+        let count = unsafe { super::loopNestGetNumLoops(self.inner) };
+        (0..count)
+            .map(|index| unsafe { Loop::from_raw(super::loopNestGetLoop(self.inner, index)) })
+            .collect()
+    }
+
+    /// Get the outermost loop of this nest.
+    pub fn outermost_loop(&self) -> Loop<'a> {
+        unsafe { Loop::from_raw(super::loopNestGetLoop(self.inner, 0)) }
+    }
+
+    /// Get the depth of this nest, i.e. the number of loops in its deepest
+    /// path from the outermost loop.
+    pub fn depth(&self) -> u32 {
+        unsafe { super::loopNestGetNestDepth(self.inner) }
+    }
+
+    /// Get the depth of the perfectly nested part of this nest, starting
+    /// from the outermost loop.
+    pub fn max_perfect_depth(&self) -> u32 {
+        unsafe { super::loopNestGetMaxPerfectDepth(self.inner) }
+    }
+
+    /// Whether this nest is perfect, i.e. each loop only contains its
+    /// sub-loop (apart from the loop control instructions).
+    pub fn is_perfect(&self) -> bool {
+        self.max_perfect_depth() == self.depth()
+    }
+
+    /// Get the function containing this nest.
+    pub fn function(&self) -> FunctionValue<'a> {
+        let function = unsafe { super::loopNestGetFunction(self.inner) };
+        unsafe { FunctionValue::new(function.cast()).unwrap() }
+    }
+}
+
+/// View of an LLVM loop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Loop<'a> {
+    inner: *mut c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> Loop<'a> {
+    unsafe fn from_raw(loop_: *mut c_void) -> Self {
+        Self {
+            inner: loop_,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the depth of this loop, outermost loops having a depth of 1.
+    pub fn depth(&self) -> u32 {
+        unsafe { super::loopGetDepth(self.inner) }
+    }
+
+    /// Get the header of this loop.
+    pub fn header(&self) -> BasicBlock<'a> {
+        let header = unsafe { super::loopGetHeader(self.inner) };
+        unsafe { BasicBlock::new(header.cast()).unwrap() }
+    }
+
+    /// Get the blocks of this loop, including the ones of its sub-loops.
+    ///
+    /// The header is always the first block.
+    pub fn blocks(&self) -> Vec<BasicBlock<'a>> {
+        let count = unsafe { super::loopGetNumBlocks(self.inner) };
+        (0..count)
+            .map(|index| unsafe {
+                BasicBlock::new(super::loopGetBlock(self.inner, index).cast()).unwrap()
+            })
+            .collect()
+    }
+
+    /// Get the loops directly contained in this loop.
+    pub fn sub_loops(&self) -> Vec<Loop<'a>> {
+        let count = unsafe { super::loopGetNumSubLoops(self.inner) };
+        (0..count)
+            .map(|index| unsafe { Loop::from_raw(super::loopGetSubLoop(self.inner, index)) })
+            .collect()
+    }
+
+    /// Whether this loop contains no other loop.
+    pub fn is_innermost(&self) -> bool {
+        unsafe { super::loopGetNumSubLoops(self.inner) == 0 }
+    }
+}
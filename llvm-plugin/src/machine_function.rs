@@ -0,0 +1,305 @@
+use std::any::type_name;
+use std::ffi::{c_void, CStr};
+use std::marker::PhantomData;
+
+use inkwell::values::FunctionValue;
+
+use crate::PreservedAnalyses;
+
+/// Trait to use for implementing a pass on an LLVM machine function.
+///
+/// Machine function passes run in the codegen pipeline of the new pass
+/// manager, e.g. with `llc -passes=<name>`, and are registered with
+/// [PassBuilder::add_machine_function_pipeline_parsing_callback].
+///
+/// `llc` only accepts `-passes` along with a `.mir` input, i.e. a module
+/// which already went through instruction selection. On Windows, plugins
+/// are linked against `opt`, and therefore cannot be loaded by `llc`.
+///
+/// [PassBuilder::add_machine_function_pipeline_parsing_callback]: crate::PassBuilder::add_machine_function_pipeline_parsing_callback
+pub trait LlvmMachineFunctionPass {
+    /// Entrypoint for the pass.
+    ///
+    /// If this function makes modifications on the given machine function,
+    /// it should return `PreservedAnalyses::None` to indicate to the
+    /// pass manager that all analyses are now invalidated.
+    fn run_pass(&self, function: &mut MachineFunction<'_>) -> PreservedAnalyses;
+
+    /// Name of the pass, as reported to LLVM tooling.
+    ///
+    /// See [LlvmModulePass::name](crate::LlvmModulePass::name).
+    fn name(&self) -> &str {
+        type_name::<Self>()
+    }
+
+    /// Whether a panic in [run_pass](Self::run_pass) should abort the
+    /// LLVM tool running this pass.
+    ///
+    /// See [LlvmModulePass::ABORT_ON_PANIC](crate::LlvmModulePass::ABORT_ON_PANIC).
+    const ABORT_ON_PANIC: bool = true;
+
+    /// Whether this pass must run, even when LLVM would skip optional passes.
+    ///
+    /// See [LlvmModulePass::REQUIRED](crate::LlvmModulePass::REQUIRED).
+    const REQUIRED: bool = false;
+}
+
+/// Struct allowing to add passes on LLVM machine functions to the pass
+/// manager pipeline.
+pub struct MachineFunctionPassManager {
+    inner: *mut c_void,
+    builder: *mut c_void,
+}
+
+impl MachineFunctionPassManager {
+    #[doc(hidden)]
+    pub unsafe fn from_raw(pass_manager: *mut c_void, builder: *mut c_void) -> Self {
+        Self {
+            inner: pass_manager,
+            builder,
+        }
+    }
+
+    /// Adds a pass to this pass manager.
+    ///
+    /// The pass is reported to LLVM tooling under the name returned by
+    /// [LlvmMachineFunctionPass::name].
+    ///
+    /// Unless [LlvmMachineFunctionPass::REQUIRED] is set, LLVM may skip this
+    /// pass (e.g. on `optnone` functions, or with `-opt-bisect-limit`).
+    pub fn add_pass<T>(&mut self, pass: T)
+    where
+        T: LlvmMachineFunctionPass,
+    {
+        let name = pass.name().to_owned();
+        let pass = Box::new(pass);
+
+        extern "C" fn pass_deleter<T>(pass: *mut c_void) {
+            crate::unwind::catch_unwind("machine function pass", type_name::<T>(), || {
+                drop(unsafe { Box::<T>::from_raw(pass.cast()) })
+            })
+        }
+
+        extern "C" fn pass_entrypoint<T>(
+            pass: *mut c_void,
+            function: *mut c_void,
+            _manager: *mut c_void,
+        ) -> PreservedAnalyses
+        where
+            T: LlvmMachineFunctionPass,
+        {
+            let pass = unsafe { &*pass.cast::<T>() };
+            let context = unsafe { super::machineFunctionGetContext(function) };
+
+            crate::unwind::catch_pass_unwind(
+                "machine function pass",
+                type_name::<T>(),
+                T::ABORT_ON_PANIC,
+                context,
+                || {
+                    let mut function = unsafe { MachineFunction::from_raw(function) };
+                    pass.run_pass(&mut function)
+                },
+            )
+        }
+
+        unsafe {
+            super::machineFunctionPassManagerAddPass(
+                self.inner,
+                Box::into_raw(pass).cast(),
+                pass_deleter::<T>,
+                pass_entrypoint::<T>,
+                name.as_ptr(),
+                name.len(),
+                T::REQUIRED,
+            )
+        }
+
+        if !self.builder.is_null() {
+            unsafe { super::passBuilderAddClassToPassName(self.builder, name.as_ptr(), name.len()) }
+        }
+    }
+}
+
+/// Read-only view of an LLVM machine function.
+pub struct MachineFunction<'a> {
+    inner: *mut c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> MachineFunction<'a> {
+    unsafe fn from_raw(function: *mut c_void) -> Self {
+        Self {
+            inner: function,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the name of this machine function, which is the one of its IR
+    /// function.
+    pub fn name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(super::machineFunctionGetName(self.inner)) }
+    }
+
+    /// Get the IR function this machine function was generated from.
+    pub fn function(&self) -> FunctionValue<'a> {
+        let function = unsafe { super::machineFunctionGetFunction(self.inner) };
+        unsafe { FunctionValue::new(function.cast()).unwrap() }
+    }
+
+    /// Get the basic blocks of this machine function, in layout order.
+    pub fn basic_blocks(&self) -> Vec<MachineBasicBlock<'a>> {
+        let first = unsafe { super::machineFunctionGetFirstBlock(self.inner) };
+        collect(first, |block| unsafe {
+            super::machineBasicBlockGetNext(block)
+        })
+        .map(|inner| MachineBasicBlock {
+            inner,
+            _marker: PhantomData,
+        })
+        .collect()
+    }
+}
+
+/// Read-only view of an LLVM machine basic block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MachineBasicBlock<'a> {
+    inner: *mut c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> MachineBasicBlock<'a> {
+    /// Get the number of this block, unique within its machine function.
+    pub fn number(&self) -> i32 {
+        unsafe { super::machineBasicBlockGetNumber(self.inner) }
+    }
+
+    /// Get the name of the IR basic block this block was generated from.
+    ///
+    /// Returns an empty string when there is no such block.
+    pub fn name(&self) -> &'a CStr {
+        unsafe { CStr::from_ptr(super::machineBasicBlockGetName(self.inner)) }
+    }
+
+    /// Get the instructions of this block.
+    ///
+    /// Instructions inside a bundle are not listed, only the bundle header.
+    pub fn instructions(&self) -> Vec<MachineInstr<'a>> {
+        let first = unsafe { super::machineBasicBlockGetFirstInstr(self.inner) };
+        collect(first, |instr| unsafe { super::machineInstrGetNext(instr) })
+            .map(|inner| MachineInstr {
+                inner,
+                _marker: PhantomData,
+            })
+            .collect()
+    }
+}
+
+/// Read-only view of an LLVM machine instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MachineInstr<'a> {
+    inner: *mut c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> MachineInstr<'a> {
+    /// Get the block containing this instruction.
+    pub fn parent(&self) -> MachineBasicBlock<'a> {
+        MachineBasicBlock {
+            inner: unsafe { super::machineInstrGetParent(self.inner) },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the target-specific opcode of this instruction.
+    pub fn opcode(&self) -> u32 {
+        unsafe { super::machineInstrGetOpcode(self.inner) }
+    }
+
+    /// Get the name of the opcode of this instruction, e.g. `MOV32rr`.
+    pub fn opcode_name(&self) -> &'a str {
+        let mut len = 0;
+        let name = unsafe { super::machineInstrGetOpcodeName(self.inner, &mut len) };
+        let name = unsafe { std::slice::from_raw_parts(name, len) };
+        std::str::from_utf8(name).expect("opcode names are ASCII identifiers")
+    }
+
+    /// Whether this instruction is a terminator of its block.
+    pub fn is_terminator(&self) -> bool {
+        unsafe { super::machineInstrIsTerminator(self.inner) }
+    }
+
+    /// Whether this instruction is a call.
+    pub fn is_call(&self) -> bool {
+        unsafe { super::machineInstrIsCall(self.inner) }
+    }
+
+    /// Get the operands of this instruction.
+    pub fn operands(&self) -> Vec<MachineOperand<'a>> {
+        let count = unsafe { super::machineInstrGetNumOperands(self.inner) };
+        (0..count)
+            .map(|index| unsafe {
+                MachineOperand::from_raw(super::machineInstrGetOperand(self.inner, index))
+            })
+            .collect()
+    }
+}
+
+/// Operand of an LLVM machine instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MachineOperand<'a> {
+    /// Physical or virtual register.
+    Register {
+        /// Register number.
+        reg: u32,
+
+        /// Whether the register is defined by the instruction.
+        is_def: bool,
+    },
+
+    /// Immediate value.
+    Immediate(i64),
+
+    /// Machine basic block, e.g. the target of a branch.
+    BasicBlock(MachineBasicBlock<'a>),
+
+    /// Any other kind of operand.
+    Other,
+}
+
+impl MachineOperand<'_> {
+    unsafe fn from_raw(operand: *mut c_void) -> Self {
+        match super::machineOperandGetKind(operand) {
+            MachineOperandKind::Register => Self::Register {
+                reg: super::machineOperandGetReg(operand),
+                is_def: super::machineOperandIsDef(operand),
+            },
+            MachineOperandKind::Immediate => Self::Immediate(super::machineOperandGetImm(operand)),
+            MachineOperandKind::BasicBlock => Self::BasicBlock(MachineBasicBlock {
+                inner: super::machineOperandGetBlock(operand),
+                _marker: PhantomData,
+            }),
+            MachineOperandKind::Other => Self::Other,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub(crate) enum MachineOperandKind {
+    Register,
+    Immediate,
+    BasicBlock,
+    Other,
+}
+
+fn collect(
+    first: *mut c_void,
+    next: impl Fn(*mut c_void) -> *mut c_void,
+) -> impl Iterator<Item = *mut c_void> {
+    std::iter::successors((!first.is_null()).then_some(first), move |&current| {
+        let next = next(current);
+        (!next.is_null()).then_some(next)
+    })
+}
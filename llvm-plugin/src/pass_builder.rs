@@ -1,4 +1,6 @@
+use std::any::type_name;
 use std::ffi::c_void;
+use std::marker::PhantomData;
 
 use super::{
     FunctionAnalysisManager, FunctionPassManager, ModuleAnalysisManager, ModulePassManager,
@@ -7,6 +9,21 @@ use super::{
 /// Main struct for registering callbacks.
 pub struct PassBuilder {
     inner: *mut c_void,
+    #[cfg(any(
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    inline_advisor: *mut c_void,
+    #[cfg(any(
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    inline_order: *mut c_void,
 }
 
 impl PassBuilder {
@@ -14,6 +31,21 @@ impl PassBuilder {
     pub unsafe fn from_raw(pass_builder: *mut c_void) -> Self {
         Self {
             inner: pass_builder,
+            #[cfg(any(
+                feature = "llvm16-0",
+                feature = "llvm17-0",
+                feature = "llvm18-1",
+                feature = "llvm19-1",
+                feature = "llvm20-1",
+            ))]
+            inline_advisor: std::ptr::null_mut(),
+            #[cfg(any(
+                feature = "llvm17-0",
+                feature = "llvm18-1",
+                feature = "llvm19-1",
+                feature = "llvm20-1",
+            ))]
+            inline_order: std::ptr::null_mut(),
         }
     }
 
@@ -25,7 +57,7 @@ impl PassBuilder {
     where
         T: Fn(&str, &mut ModulePassManager) -> PipelineParsing + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -36,22 +68,21 @@ impl PassBuilder {
         where
             T: Fn(&str, &mut ModulePassManager) -> PipelineParsing + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
-            let name = unsafe { std::str::from_utf8_unchecked(name) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
-
-            let res = cb(name, &mut manager);
-
-            let _ = Box::into_raw(cb);
-            matches!(res, PipelineParsing::Parsed)
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                matches!(cb(name, &mut manager), PipelineParsing::Parsed)
+            })
         }
 
         unsafe {
             super::passBuilderAddModulePipelineParsingCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -65,7 +96,7 @@ impl PassBuilder {
     where
         T: Fn(&str, &mut FunctionPassManager) -> PipelineParsing + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -76,22 +107,157 @@ impl PassBuilder {
         where
             T: Fn(&str, &mut FunctionPassManager) -> PipelineParsing + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
-            let name = unsafe { std::str::from_utf8_unchecked(name) };
-            let mut manager = unsafe { FunctionPassManager::from_raw(manager) };
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let mut manager = unsafe { FunctionPassManager::from_raw(manager, *builder) };
+                matches!(cb(name, &mut manager), PipelineParsing::Parsed)
+            })
+        }
 
-            let res = cb(name, &mut manager);
+        unsafe {
+            super::passBuilderAddFunctionPipelineParsingCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<(*mut c_void, T)>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
 
-            let _ = Box::into_raw(cb);
-            matches!(res, PipelineParsing::Parsed)
+    /// Register a new pipeline parsing callback.
+    ///
+    /// These callbacks can be used to parse a single pass name, and populate
+    /// the given [LoopPassManager](crate::LoopPassManager) accordingly.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn add_loop_pipeline_parsing_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, &mut crate::LoopPassManager) -> PipelineParsing + 'static,
+    {
+        let cb = Box::new((self.inner, cb));
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            manager: *mut c_void,
+        ) -> bool
+        where
+            T: Fn(&str, &mut crate::LoopPassManager) -> PipelineParsing + 'static,
+        {
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let mut manager = unsafe { crate::LoopPassManager::from_raw(manager, *builder) };
+                matches!(cb(name, &mut manager), PipelineParsing::Parsed)
+            })
         }
 
         unsafe {
-            super::passBuilderAddFunctionPipelineParsingCallback(
+            super::passBuilderAddLoopPipelineParsingCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Register a new pipeline parsing callback.
+    ///
+    /// These callbacks can be used to parse a single pass name, and populate
+    /// the given [MachineFunctionPassManager](crate::MachineFunctionPassManager)
+    /// accordingly.
+    #[cfg(any(doc, feature = "llvm19-1", feature = "llvm20-1"))]
+    pub fn add_machine_function_pipeline_parsing_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, &mut crate::MachineFunctionPassManager) -> PipelineParsing + 'static,
+    {
+        let cb = Box::new((self.inner, cb));
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            manager: *mut c_void,
+        ) -> bool
+        where
+            T: Fn(&str, &mut crate::MachineFunctionPassManager) -> PipelineParsing + 'static,
+        {
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let mut manager =
+                    unsafe { crate::MachineFunctionPassManager::from_raw(manager, *builder) };
+                matches!(cb(name, &mut manager), PipelineParsing::Parsed)
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddMachineFunctionPipelineParsingCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<(*mut c_void, T)>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Register a new top-level pipeline parsing callback.
+    ///
+    /// These callbacks are given the whole pipeline text (e.g. the `--passes`
+    /// parameter of opt) as a list of [PipelineElement], which allows defining
+    /// aliases expanding into a full pipeline (e.g. `secure-default<O2>`).
+    ///
+    /// If the callback returns [PipelineParsing::NotParsed], LLVM parses the
+    /// pipeline as usual.
+    pub fn add_top_level_pipeline_parsing_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&mut ModulePassManager, &[PipelineElement<'_>]) -> PipelineParsing + 'static,
+    {
+        let cb = Box::new((self.inner, cb));
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            manager: *mut c_void,
+            pipeline_ptr: *const c_void,
+            pipeline_len: usize,
+        ) -> bool
+        where
+            T: Fn(&mut ModulePassManager, &[PipelineElement<'_>]) -> PipelineParsing + 'static,
+        {
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let pipeline =
+                    unsafe { PipelineElement::from_raw_parts(pipeline_ptr, pipeline_len) };
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                matches!(cb(&mut manager, &pipeline), PipelineParsing::Parsed)
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddTopLevelPipelineParsingCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -105,25 +271,26 @@ impl PassBuilder {
     where
         T: Fn(&mut ModuleAnalysisManager) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((cb, self.target_machine()));
 
         extern "C" fn callback_entrypoint<T>(cb: *const c_void, manager: *mut c_void)
         where
             T: Fn(&mut ModuleAnalysisManager) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModuleAnalysisManager::from_raw(manager, None) };
+            let (cb, target) = unsafe { &*cb.cast::<(T, Option<crate::TargetMachineInfo>)>() };
 
-            cb(&mut manager);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager =
+                    unsafe { ModuleAnalysisManager::from_raw(manager, None, target.as_ref()) };
+                cb(&mut manager);
+            })
         }
 
         unsafe {
             super::passBuilderAddModuleAnalysisRegistrationCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(T, Option<crate::TargetMachineInfo>)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -137,25 +304,26 @@ impl PassBuilder {
     where
         T: Fn(&mut FunctionAnalysisManager) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((cb, self.target_machine()));
 
         extern "C" fn callback_entrypoint<T>(cb: *const c_void, manager: *mut c_void)
         where
             T: Fn(&mut FunctionAnalysisManager) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { FunctionAnalysisManager::from_raw(manager, None) };
+            let (cb, target) = unsafe { &*cb.cast::<(T, Option<crate::TargetMachineInfo>)>() };
 
-            cb(&mut manager);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager =
+                    unsafe { FunctionAnalysisManager::from_raw(manager, None, target.as_ref()) };
+                cb(&mut manager);
+            })
         }
 
         unsafe {
             super::passBuilderAddFunctionAnalysisRegistrationCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(T, Option<crate::TargetMachineInfo>)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -175,7 +343,7 @@ impl PassBuilder {
     where
         T: Fn(&mut FunctionPassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -184,19 +352,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut FunctionPassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { FunctionPassManager::from_raw(manager) };
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            cb(&mut manager, opt);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { FunctionPassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddPeepholeEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -214,7 +382,7 @@ impl PassBuilder {
     where
         T: Fn(&mut FunctionPassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -223,19 +391,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut FunctionPassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { FunctionPassManager::from_raw(manager) };
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            cb(&mut manager, opt);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { FunctionPassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddScalarOptimizerLateEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -253,7 +421,7 @@ impl PassBuilder {
     where
         T: Fn(&mut FunctionPassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -262,19 +430,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut FunctionPassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { FunctionPassManager::from_raw(manager) };
-
-            cb(&mut manager, opt);
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { FunctionPassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddVectorizerStartEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -304,7 +472,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -313,19 +481,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
-
-            cb(&mut manager, opt);
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddPipelineStartEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -353,7 +521,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -362,19 +530,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            cb(&mut manager, opt);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddPipelineEarlySimplificationEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -392,7 +560,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel, ThinOrFullLTOPhase) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -402,19 +570,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel, ThinOrFullLTOPhase) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
-
-            cb(&mut manager, opt, phase);
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt, phase);
+            })
         }
 
         unsafe {
             super::passBuilderAddPipelineEarlySimplificationEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -443,7 +611,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -452,19 +620,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
-
-            cb(&mut manager, opt);
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddOptimizerLastEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -482,7 +650,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel, ThinOrFullLTOPhase) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -492,19 +660,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel, ThinOrFullLTOPhase) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            cb(&mut manager, opt, phase);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt, phase);
+            })
         }
 
         unsafe {
             super::passBuilderAddOptimizerLastEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -530,7 +698,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -539,19 +707,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            cb(&mut manager, opt);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddFullLinkTimeOptimizationEarlyEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -577,7 +745,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -586,19 +754,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
-
-            cb(&mut manager, opt);
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddFullLinkTimeOptimizationLastEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -623,7 +791,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -632,19 +800,19 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
-
-            cb(&mut manager, opt);
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt);
+            })
         }
 
         unsafe {
             super::passBuilderAddOptimizerEarlyEPCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
-                callback_deleter::<T>,
+                callback_deleter::<(*mut c_void, T)>,
                 callback_entrypoint::<T>,
             )
         }
@@ -662,7 +830,7 @@ impl PassBuilder {
     where
         T: Fn(&mut ModulePassManager, OptimizationLevel, ThinOrFullLTOPhase) + 'static,
     {
-        let cb = Box::new(cb);
+        let cb = Box::new((self.inner, cb));
 
         extern "C" fn callback_entrypoint<T>(
             cb: *const c_void,
@@ -672,16 +840,136 @@ impl PassBuilder {
         ) where
             T: Fn(&mut ModulePassManager, OptimizationLevel, ThinOrFullLTOPhase) + 'static,
         {
-            let cb = unsafe { Box::<T>::from_raw(cb as *mut _) };
-            let mut manager = unsafe { ModulePassManager::from_raw(manager) };
+            let (builder, cb) = unsafe { &*cb.cast::<(*mut c_void, T)>() };
 
-            cb(&mut manager, opt, phase);
-
-            let _ = Box::into_raw(cb);
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let mut manager = unsafe { ModulePassManager::from_raw(manager, *builder) };
+                cb(&mut manager, opt, phase);
+            })
         }
 
         unsafe {
             super::passBuilderAddOptimizerEarlyEPCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<(*mut c_void, T)>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Register a new callback deciding whether an optional pass should run.
+    ///
+    /// The callback is given the name of the pass, and the IR unit it is
+    /// about to run on. Returning `false` skips the pass on this IR unit.
+    ///
+    /// Passes which are required (e.g. the `always-inline` pass, or pass
+    /// managers themselves) are never given to this callback. The pass is
+    /// skipped as soon as one of the registered callbacks returns `false`.
+    ///
+    /// Pass names are the ones given by the LLVM tool to instrumentation,
+    /// which usually are class names (e.g. `LoopUnrollPass`).
+    ///
+    /// If the LLVM tool running the plugin doesn't support pass
+    /// instrumentation, the callback is never called.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn add_should_run_optional_pass_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, &crate::IrUnit<'_>) -> bool + 'static,
+    {
+        let cb = Box::new(cb);
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            kind: crate::IrUnitKind,
+            unit: *const c_void,
+        ) -> bool
+        where
+            T: Fn(&str, &crate::IrUnit<'_>) -> bool + 'static,
+        {
+            let cb = unsafe { &*cb.cast::<T>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let unit = unsafe { crate::IrUnit::from_raw(kind, unit) };
+                cb(name, &unit)
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddShouldRunOptionalPassCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<T>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Register a new callback to be triggered before running a pass.
+    ///
+    /// The callback is given the name of the pass, and the IR unit it is
+    /// about to run on. It is called for every pass of the pipeline,
+    /// including builtin ones, unless the pass was skipped.
+    ///
+    /// Pass names are the ones given by the LLVM tool to instrumentation,
+    /// which usually are class names (e.g. `InstCombinePass`).
+    ///
+    /// If the LLVM tool running the plugin doesn't support pass
+    /// instrumentation, the callback is never called.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn add_before_pass_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, &crate::IrUnit<'_>) + 'static,
+    {
+        let cb = Box::new(cb);
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            kind: crate::IrUnitKind,
+            unit: *const c_void,
+        ) where
+            T: Fn(&str, &crate::IrUnit<'_>) + 'static,
+        {
+            let cb = unsafe { &*cb.cast::<T>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let unit = unsafe { crate::IrUnit::from_raw(kind, unit) };
+                cb(name, &unit);
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddBeforePassCallback(
                 self.inner,
                 Box::into_raw(cb).cast(),
                 callback_deleter::<T>,
@@ -689,10 +977,396 @@ impl PassBuilder {
             )
         }
     }
+
+    /// Register a new callback to be triggered after running a pass.
+    ///
+    /// The callback is given the name of the pass, the IR unit it ran on,
+    /// and whether the pass preserved all analyses.
+    ///
+    /// If the pass invalidated the IR unit (e.g. by deleting it), the
+    /// callbacks registered with
+    /// [add_after_pass_invalidated_callback](Self::add_after_pass_invalidated_callback)
+    /// are triggered instead.
+    ///
+    /// If the LLVM tool running the plugin doesn't support pass
+    /// instrumentation, the callback is never called.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn add_after_pass_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, &crate::IrUnit<'_>, crate::PreservedAnalyses) + 'static,
+    {
+        let cb = Box::new(cb);
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            kind: crate::IrUnitKind,
+            unit: *const c_void,
+            preserved: crate::PreservedAnalyses,
+        ) where
+            T: Fn(&str, &crate::IrUnit<'_>, crate::PreservedAnalyses) + 'static,
+        {
+            let cb = unsafe { &*cb.cast::<T>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let unit = unsafe { crate::IrUnit::from_raw(kind, unit) };
+                cb(name, &unit, preserved);
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddAfterPassCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<T>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Register a new callback to be triggered after running a pass which
+    /// invalidated the IR unit it ran on.
+    ///
+    /// The callback is given the name of the pass, and whether the pass
+    /// preserved all analyses.
+    ///
+    /// If the LLVM tool running the plugin doesn't support pass
+    /// instrumentation, the callback is never called.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn add_after_pass_invalidated_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, crate::PreservedAnalyses) + 'static,
+    {
+        let cb = Box::new(cb);
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            preserved: crate::PreservedAnalyses,
+        ) where
+            T: Fn(&str, crate::PreservedAnalyses) + 'static,
+        {
+            let cb = unsafe { &*cb.cast::<T>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                cb(name, preserved);
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddAfterPassInvalidatedCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<T>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Register a new callback to be triggered before running an analysis.
+    ///
+    /// The callback is given the name of the analysis, and the IR unit it is
+    /// about to run on.
+    ///
+    /// If the LLVM tool running the plugin doesn't support pass
+    /// instrumentation, the callback is never called.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn add_before_analysis_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, &crate::IrUnit<'_>) + 'static,
+    {
+        let cb = Box::new(cb);
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            kind: crate::IrUnitKind,
+            unit: *const c_void,
+        ) where
+            T: Fn(&str, &crate::IrUnit<'_>) + 'static,
+        {
+            let cb = unsafe { &*cb.cast::<T>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let unit = unsafe { crate::IrUnit::from_raw(kind, unit) };
+                cb(name, &unit);
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddBeforeAnalysisCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<T>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Register a new callback to be triggered after running an analysis.
+    ///
+    /// The callback is given the name of the analysis, and the IR unit it
+    /// ran on.
+    ///
+    /// If the LLVM tool running the plugin doesn't support pass
+    /// instrumentation, the callback is never called.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn add_after_analysis_callback<T>(&mut self, cb: T)
+    where
+        T: Fn(&str, &crate::IrUnit<'_>) + 'static,
+    {
+        let cb = Box::new(cb);
+
+        extern "C" fn callback_entrypoint<T>(
+            cb: *const c_void,
+            name_ptr: *const u8,
+            name_len: usize,
+            kind: crate::IrUnitKind,
+            unit: *const c_void,
+        ) where
+            T: Fn(&str, &crate::IrUnit<'_>) + 'static,
+        {
+            let cb = unsafe { &*cb.cast::<T>() };
+
+            crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+                let name = unsafe { std::slice::from_raw_parts(name_ptr, name_len) };
+                let name = unsafe { std::str::from_utf8_unchecked(name) };
+                let unit = unsafe { crate::IrUnit::from_raw(kind, unit) };
+                cb(name, &unit);
+            })
+        }
+
+        unsafe {
+            super::passBuilderAddAfterAnalysisCallback(
+                self.inner,
+                Box::into_raw(cb).cast(),
+                callback_deleter::<T>,
+                callback_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Set the inline advisor of the plugin.
+    ///
+    /// The advisor replaces the default one of LLVM for the inliner
+    /// passes of the pipelines built afterward. Setting an advisor again
+    /// through the same builder replaces the previous one.
+    ///
+    /// Only the inliners querying the inline advisor analysis use it, i.e.
+    /// the default pipelines, `inliner-wrapper` and `module-inline` with
+    /// opt. A standalone `inline` pass falls back to the default advisor
+    /// of LLVM.
+    #[cfg(any(
+        doc,
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn set_inline_advisor<T>(&mut self, advisor: T)
+    where
+        T: crate::LlvmInlineAdvisor + 'static,
+    {
+        let advisor = Box::new(advisor);
+
+        extern "C" fn advisor_entrypoint<T>(
+            advisor: *const c_void,
+            caller: *mut c_void,
+            callee: *mut c_void,
+            call: *mut c_void,
+            cost: *const crate::inline_advisor::InlineCostFFI,
+            reason: *mut c_void,
+        ) -> bool
+        where
+            T: crate::LlvmInlineAdvisor + 'static,
+        {
+            let advisor = unsafe { &*advisor.cast::<T>() };
+
+            crate::unwind::catch_unwind("inline advisor", type_name::<T>(), || {
+                let call_site =
+                    unsafe { crate::InlineCallSite::from_raw(caller, callee, call, cost) };
+                let advice = advisor.advise(&call_site);
+                if let Some(why) = advice.reason() {
+                    unsafe { super::inlineAdviceSetReason(reason, why.as_ptr(), why.len()) };
+                }
+                advice.is_inlining_recommended()
+            })
+        }
+
+        unsafe {
+            super::passBuilderSetInlineAdvisor(
+                self.inner,
+                &mut self.inline_advisor,
+                Box::into_raw(advisor).cast(),
+                callback_deleter::<T>,
+                advisor_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Set the inline order of the plugin.
+    ///
+    /// The inline order replaces the default one of LLVM for every module
+    /// inliner pass of the pipelines built afterward. Setting an inline
+    /// order again through the same builder replaces the previous one.
+    #[cfg(any(
+        doc,
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn set_inline_order<T>(&mut self, order: T)
+    where
+        T: crate::LlvmInlineOrder + 'static,
+    {
+        let order = Box::new(order);
+
+        extern "C" fn order_entrypoint<T>(
+            order: *const c_void,
+            caller: *mut c_void,
+            callee: *mut c_void,
+            call: *mut c_void,
+            cost: *const crate::inline_advisor::InlineCostFFI,
+        ) -> i64
+        where
+            T: crate::LlvmInlineOrder + 'static,
+        {
+            let order = unsafe { &*order.cast::<T>() };
+
+            crate::unwind::catch_unwind("inline order", type_name::<T>(), || {
+                let call_site =
+                    unsafe { crate::InlineCallSite::from_raw(caller, callee, call, cost) };
+                order.priority(&call_site)
+            })
+        }
+
+        unsafe {
+            super::passBuilderSetInlineOrder(
+                self.inner,
+                &mut self.inline_order,
+                Box::into_raw(order).cast(),
+                callback_deleter::<T>,
+                order_entrypoint::<T>,
+            )
+        }
+    }
+
+    /// Get the tuning options of the default pipelines.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use llvm_plugin::PassBuilder;
+    /// # fn plugin_registrar(builder: &mut PassBuilder) {
+    /// let tuning = builder.pipeline_tuning_options();
+    /// builder.add_vectorizer_start_ep_callback(move |manager, _| {
+    ///     if tuning.loop_vectorization() {
+    ///         // add passes preparing loops for vectorization
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn pipeline_tuning_options(&self) -> crate::PipelineTuningOptions {
+        unsafe { super::passBuilderGetPipelineTuningOptions(self.inner) }
+    }
+
+    /// Get the profile-guided optimization options.
+    ///
+    /// Returns `None` when PGO is not in use.
+    pub fn pgo_options(&self) -> Option<crate::PGOOptions> {
+        let pgo_options = unsafe { super::passBuilderGetPGOOptions(self.inner) };
+        if pgo_options.is_null() {
+            return None;
+        }
+
+        Some(unsafe { crate::PGOOptions::from_raw(pgo_options) })
+    }
+
+    /// Get a view of the target machine the pipelines are built for.
+    ///
+    /// Returns `None` when no target machine was given to LLVM, e.g. when
+    /// `opt` runs on a module without target triple.
+    ///
+    /// Running passes and analyses get the same view from
+    /// [ModuleAnalysisManager::target_machine] and
+    /// [FunctionAnalysisManager::target_machine].
+    pub fn target_machine(&self) -> Option<crate::TargetMachineInfo> {
+        target_machine(self.inner)
+    }
+}
+
+pub(crate) fn target_machine(builder: *mut c_void) -> Option<crate::TargetMachineInfo> {
+    if builder.is_null() {
+        return None;
+    }
+
+    let target_machine = unsafe { super::passBuilderGetTargetMachine(builder) };
+    if target_machine.is_null() {
+        return None;
+    }
+
+    Some(unsafe { crate::TargetMachineInfo::from_raw(target_machine) })
 }
 
 extern "C" fn callback_deleter<T>(cb: *const c_void) {
-    drop(unsafe { Box::<T>::from_raw(cb as *mut _) })
+    crate::unwind::catch_unwind("callback", type_name::<T>(), || {
+        drop(unsafe { Box::<T>::from_raw(cb as *mut _) })
+    })
 }
 
 /// Enum describing whether a pipeline parsing callback
@@ -706,11 +1380,54 @@ pub enum PipelineParsing {
     NotParsed,
 }
 
+/// Element of a textual pipeline.
+///
+/// For instance, the pipeline `function(instcombine),globaldce` is made of
+/// the elements `function` and `globaldce`, the former having `instcombine`
+/// as inner pipeline.
+#[derive(Clone, Copy)]
+pub struct PipelineElement<'a> {
+    inner: *const c_void,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineElement<'a> {
+    unsafe fn from_raw_parts(elements: *const c_void, len: usize) -> Vec<Self> {
+        (0..len)
+            .map(|index| Self {
+                inner: unsafe { super::pipelineElementAt(elements, index) },
+                _marker: PhantomData,
+            })
+            .collect()
+    }
+
+    /// Get the name of this element.
+    pub fn name(&self) -> &'a str {
+        let mut len = 0;
+        let ptr = unsafe { super::pipelineElementGetName(self.inner, &mut len) };
+        if len == 0 {
+            return "";
+        }
+
+        let name = unsafe { std::slice::from_raw_parts(ptr, len) };
+        unsafe { std::str::from_utf8_unchecked(name) }
+    }
+
+    /// Get the elements nested in this one, between parentheses.
+    pub fn inner_pipeline(&self) -> Vec<PipelineElement<'a>> {
+        let mut len = 0;
+        unsafe {
+            let elements = super::pipelineElementGetInnerPipeline(self.inner, &mut len);
+            Self::from_raw_parts(elements, len)
+        }
+    }
+}
+
 /// Enum for the LLVM-provided high-level optimization levels.
 ///
 /// Each level has a specific goal and rationale.
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum OptimizationLevel {
     /// This level disables as many optimizations as possible.
     O0,
@@ -1,36 +1,47 @@
+use std::any::{type_name, Any};
 use std::ffi::c_void;
-
-use inkwell::module::Module;
-use inkwell::values::FunctionValue;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
 
 use super::{
-    FunctionAnalysisManager, LlvmFunctionPass, LlvmModulePass, ModuleAnalysisManager,
-    PreservedAnalyses,
+    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, LlvmModulePass,
+    ModuleAnalysisManager, ModuleRef, OptimizationLevel, PreservedAnalyses, TargetMachineInfo,
 };
 
 /// Struct allowing to add passes on LLVM IR modules to the pass manager
 /// pipeline.
 pub struct ModulePassManager {
     inner: *mut c_void,
+    builder: *mut c_void,
 }
 
 impl ModulePassManager {
     #[doc(hidden)]
-    pub unsafe fn from_raw(pass_manager: *mut c_void) -> Self {
+    pub unsafe fn from_raw(pass_manager: *mut c_void, builder: *mut c_void) -> Self {
         Self {
             inner: pass_manager,
+            builder,
         }
     }
 
     /// Adds a pass to this pass manager.
+    ///
+    /// The pass is reported to LLVM tooling under the name returned by
+    /// [LlvmModulePass::name].
+    ///
+    /// Unless [LlvmModulePass::REQUIRED] is set, LLVM may skip this pass
+    /// (e.g. on `optnone` functions, or with `-opt-bisect-limit`).
     pub fn add_pass<T>(&mut self, pass: T)
     where
         T: LlvmModulePass,
     {
-        let pass = Box::new(pass);
+        let name = pass.name().to_owned();
+        let pass = Box::new((pass, self.target_machine()));
 
         extern "C" fn pass_deleter<T>(pass: *mut c_void) {
-            drop(unsafe { Box::<T>::from_raw(pass.cast()) })
+            crate::unwind::catch_unwind("module pass", type_name::<T>(), || {
+                drop(unsafe { Box::<(T, Option<TargetMachineInfo>)>::from_raw(pass.cast()) })
+            })
         }
 
         extern "C" fn pass_entrypoint<T>(
@@ -41,16 +52,21 @@ impl ModulePassManager {
         where
             T: LlvmModulePass,
         {
-            let pass = unsafe { Box::<T>::from_raw(pass.cast()) };
-            let mut module = unsafe { Module::new(module.cast()) };
-            let manager = unsafe { ModuleAnalysisManager::from_raw(manager, None) };
-
-            let preserve = pass.run_pass(&mut module, &manager);
+            let (pass, target) = unsafe { &*pass.cast::<(T, Option<TargetMachineInfo>)>() };
+            let context = unsafe { super::moduleGetContext(module) };
 
-            let _ = Box::into_raw(pass);
-            std::mem::forget(module);
-
-            preserve
+            crate::unwind::catch_pass_unwind(
+                "module pass",
+                type_name::<T>(),
+                T::ABORT_ON_PANIC,
+                context,
+                || {
+                    let mut module = unsafe { ModuleRef::from_raw(module) };
+                    let manager =
+                        unsafe { ModuleAnalysisManager::from_raw(manager, None, target.as_ref()) };
+                    pass.run_pass(&mut module, &manager)
+                },
+            )
         }
 
         unsafe {
@@ -59,8 +75,26 @@ impl ModulePassManager {
                 Box::into_raw(pass).cast(),
                 pass_deleter::<T>,
                 pass_entrypoint::<T>,
+                name.as_ptr(),
+                name.len(),
+                T::REQUIRED,
             )
         }
+
+        #[cfg(any(
+            feature = "llvm12-0",
+            feature = "llvm13-0",
+            feature = "llvm14-0",
+            feature = "llvm15-0",
+            feature = "llvm16-0",
+            feature = "llvm17-0",
+            feature = "llvm18-1",
+            feature = "llvm19-1",
+            feature = "llvm20-1",
+        ))]
+        if !self.builder.is_null() {
+            unsafe { super::passBuilderAddClassToPassName(self.builder, name.as_ptr(), name.len()) }
+        }
     }
 
     /// Returns if the pass manager contains any passes.
@@ -79,31 +113,186 @@ impl ModulePassManager {
     pub fn is_empty(&self) -> bool {
         unsafe { super::modulePassManagerIsEmpty(self.inner) }
     }
+
+    pub(crate) fn add_invalidate_analysis_pass(&mut self, id: AnalysisKey) {
+        unsafe { super::modulePassManagerAddInvalidateAnalysisPass(self.inner, id) }
+    }
+
+    /// Get a view of the target machine the pipeline is built for.
+    ///
+    /// Returns `None` when no target machine was given to LLVM, or when
+    /// this pass manager was not created by a
+    /// [PassBuilder](crate::PassBuilder).
+    ///
+    /// Passes added here can also get it while running, from
+    /// [ModuleAnalysisManager::target_machine].
+    pub fn target_machine(&self) -> Option<crate::TargetMachineInfo> {
+        crate::pass_builder::target_machine(self.builder)
+    }
+
+    /// Adds a function pass manager to this pass manager, running its
+    /// passes on every function of the module.
+    ///
+    /// This allows scheduling function passes at module-level extension
+    /// points (e.g. [PassBuilder::add_pipeline_start_ep_callback]).
+    ///
+    /// [PassBuilder::add_pipeline_start_ep_callback]: crate::PassBuilder::add_pipeline_start_ep_callback
+    pub fn add_function_pass_manager<F>(&mut self, cb: F)
+    where
+        F: FnOnce(&mut FunctionPassManager),
+    {
+        let mut nested = NestedManager::new(cb, self.builder, FunctionPassManager::from_raw);
+        unsafe {
+            super::modulePassManagerAddFunctionPassManager(
+                self.inner,
+                (&mut nested as *mut NestedManager<_, _>).cast(),
+                nested_entrypoint::<FunctionPassManager, F>,
+            )
+        }
+        nested.resume_panic();
+    }
+
+    /// Adds a nested module pass manager to this pass manager.
+    pub fn add_module_pass_manager<F>(&mut self, cb: F)
+    where
+        F: FnOnce(&mut ModulePassManager),
+    {
+        let mut nested = NestedManager::new(cb, self.builder, ModulePassManager::from_raw);
+        unsafe {
+            super::modulePassManagerAddModulePassManager(
+                self.inner,
+                (&mut nested as *mut NestedManager<_, _>).cast(),
+                nested_entrypoint::<ModulePassManager, F>,
+            )
+        }
+        nested.resume_panic();
+    }
+
+    /// Adds the default optimization pipeline of LLVM for the given level
+    /// (i.e. `default<Ox>`) to this pass manager.
+    ///
+    /// At [OptimizationLevel::O0], this is the O0 pipeline of LLVM, which
+    /// only runs the passes required for correctness.
+    pub fn add_per_module_default_pipeline(&mut self, level: OptimizationLevel) {
+        assert!(!self.builder.is_null(), "no pass builder available");
+        unsafe {
+            super::modulePassManagerAddPerModuleDefaultPipeline(self.inner, self.builder, level)
+        }
+    }
+
+    /// Adds the module optimization pipeline of LLVM for the given level to
+    /// this pass manager.
+    ///
+    /// This is the part of the default pipeline running after the module
+    /// simplification.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level is [OptimizationLevel::O0], which LLVM doesn't
+    /// support here, or if this pass manager was not created by a
+    /// [PassBuilder](crate::PassBuilder).
+    pub fn add_module_optimization_pipeline(&mut self, level: OptimizationLevel) {
+        assert!(!self.builder.is_null(), "no pass builder available");
+        assert_ne!(
+            level,
+            OptimizationLevel::O0,
+            "O0 has no module optimization pipeline"
+        );
+        unsafe {
+            super::modulePassManagerAddModuleOptimizationPipeline(self.inner, self.builder, level)
+        }
+    }
+
+    /// Adds the ThinLTO pre-link pipeline of LLVM for the given level (i.e.
+    /// `thinlto-pre-link<Ox>`) to this pass manager.
+    pub fn add_thin_lto_pre_link_default_pipeline(&mut self, level: OptimizationLevel) {
+        assert!(!self.builder.is_null(), "no pass builder available");
+        unsafe {
+            super::modulePassManagerAddThinLTOPreLinkDefaultPipeline(
+                self.inner,
+                self.builder,
+                level,
+            )
+        }
+    }
+
+    /// Adds the full LTO pre-link pipeline of LLVM for the given level (i.e.
+    /// `lto-pre-link<Ox>`) to this pass manager.
+    pub fn add_lto_pre_link_default_pipeline(&mut self, level: OptimizationLevel) {
+        assert!(!self.builder.is_null(), "no pass builder available");
+        unsafe {
+            super::modulePassManagerAddLTOPreLinkDefaultPipeline(self.inner, self.builder, level)
+        }
+    }
+
+    /// Parses the given textual pipeline with the active [PassBuilder], and
+    /// adds the resulting passes to this pass manager.
+    ///
+    /// The text uses the syntax of the `--passes` parameter of [opt], e.g.
+    /// `instcombine,simplifycfg`. On failure, the passes preceding the
+    /// faulty element of the pipeline may already have been added.
+    ///
+    /// [PassBuilder]: crate::PassBuilder
+    /// [opt]: https://www.llvm.org/docs/CommandGuide/opt.html
+    pub fn add_pipeline_text(&mut self, text: &str) -> Result<(), PipelineParseError> {
+        if self.builder.is_null() {
+            return Err(PipelineParseError::new("no pass builder available"));
+        }
+
+        let mut message = String::new();
+        let success = unsafe {
+            super::modulePassManagerAddPipelineText(
+                self.inner,
+                self.builder,
+                text.as_ptr(),
+                text.len(),
+                (&mut message as *mut String).cast(),
+                write_error,
+            )
+        };
+
+        if success {
+            Ok(())
+        } else {
+            Err(PipelineParseError { message })
+        }
+    }
 }
 
 /// Struct allowing to add passes on LLVM IR functions to the pass manager
 /// pipeline.
 pub struct FunctionPassManager {
     inner: *mut c_void,
+    builder: *mut c_void,
 }
 
 impl FunctionPassManager {
     #[doc(hidden)]
-    pub unsafe fn from_raw(pass_manager: *mut c_void) -> Self {
+    pub unsafe fn from_raw(pass_manager: *mut c_void, builder: *mut c_void) -> Self {
         Self {
             inner: pass_manager,
+            builder,
         }
     }
 
     /// Adds a pass to this pass manager.
+    ///
+    /// The pass is reported to LLVM tooling under the name returned by
+    /// [LlvmFunctionPass::name].
+    ///
+    /// Unless [LlvmFunctionPass::REQUIRED] is set, LLVM may skip this pass
+    /// (e.g. on `optnone` functions, or with `-opt-bisect-limit`).
     pub fn add_pass<T>(&mut self, pass: T)
     where
         T: LlvmFunctionPass,
     {
-        let pass = Box::new(pass);
+        let name = pass.name().to_owned();
+        let pass = Box::new((pass, self.target_machine()));
 
         extern "C" fn pass_deleter<T>(pass: *mut c_void) {
-            drop(unsafe { Box::<T>::from_raw(pass.cast()) })
+            crate::unwind::catch_unwind("function pass", type_name::<T>(), || {
+                drop(unsafe { Box::<(T, Option<TargetMachineInfo>)>::from_raw(pass.cast()) })
+            })
         }
 
         extern "C" fn pass_entrypoint<T>(
@@ -114,17 +303,22 @@ impl FunctionPassManager {
         where
             T: LlvmFunctionPass,
         {
-            let pass = unsafe { Box::<T>::from_raw(pass.cast()) };
-            let mut function = unsafe { FunctionValue::new(function.cast()).unwrap() };
-            let manager = unsafe { FunctionAnalysisManager::from_raw(manager, None) };
-
-            let preserve = pass.run_pass(&mut function, &manager);
+            let (pass, target) = unsafe { &*pass.cast::<(T, Option<TargetMachineInfo>)>() };
+            let context = unsafe { super::functionGetContext(function) };
 
-            let _ = Box::into_raw(pass);
-            #[allow(forgetting_copy_types)]
-            std::mem::forget(function);
-
-            preserve
+            crate::unwind::catch_pass_unwind(
+                "function pass",
+                type_name::<T>(),
+                T::ABORT_ON_PANIC,
+                context,
+                || {
+                    let mut function = unsafe { FunctionRef::from_raw(function) };
+                    let manager = unsafe {
+                        FunctionAnalysisManager::from_raw(manager, None, target.as_ref())
+                    };
+                    pass.run_pass(&mut function, &manager)
+                },
+            )
         }
 
         unsafe {
@@ -133,8 +327,26 @@ impl FunctionPassManager {
                 Box::into_raw(pass).cast(),
                 pass_deleter::<T>,
                 pass_entrypoint::<T>,
+                name.as_ptr(),
+                name.len(),
+                T::REQUIRED,
             )
         }
+
+        #[cfg(any(
+            feature = "llvm12-0",
+            feature = "llvm13-0",
+            feature = "llvm14-0",
+            feature = "llvm15-0",
+            feature = "llvm16-0",
+            feature = "llvm17-0",
+            feature = "llvm18-1",
+            feature = "llvm19-1",
+            feature = "llvm20-1",
+        ))]
+        if !self.builder.is_null() {
+            unsafe { super::passBuilderAddClassToPassName(self.builder, name.as_ptr(), name.len()) }
+        }
     }
 
     /// Returns if the pass manager contains any passes.
@@ -153,4 +365,168 @@ impl FunctionPassManager {
     pub fn is_empty(&self) -> bool {
         unsafe { super::functionPassManagerIsEmpty(self.inner) }
     }
+
+    pub(crate) fn add_invalidate_analysis_pass(&mut self, id: AnalysisKey) {
+        unsafe { super::functionPassManagerAddInvalidateAnalysisPass(self.inner, id) }
+    }
+
+    /// Get a view of the target machine the pipeline is built for.
+    ///
+    /// Returns `None` when no target machine was given to LLVM, or when
+    /// this pass manager was not created by a
+    /// [PassBuilder](crate::PassBuilder).
+    ///
+    /// Passes added here can also get it while running, from
+    /// [FunctionAnalysisManager::target_machine].
+    pub fn target_machine(&self) -> Option<crate::TargetMachineInfo> {
+        crate::pass_builder::target_machine(self.builder)
+    }
+
+    /// Adds the function simplification pipeline of LLVM for the given level
+    /// to this pass manager.
+    ///
+    /// This is the pipeline run on each function by the default pipeline,
+    /// interleaved with the inliner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level is [OptimizationLevel::O0], which LLVM doesn't
+    /// support here, or if this pass manager was not created by a
+    /// [PassBuilder](crate::PassBuilder).
+    pub fn add_function_simplification_pipeline(&mut self, level: OptimizationLevel) {
+        assert!(!self.builder.is_null(), "no pass builder available");
+        assert_ne!(
+            level,
+            OptimizationLevel::O0,
+            "O0 has no function simplification pipeline"
+        );
+        unsafe {
+            super::functionPassManagerAddFunctionSimplificationPipeline(
+                self.inner,
+                self.builder,
+                level,
+            )
+        }
+    }
+
+    /// Parses the given textual pipeline with the active [PassBuilder], and
+    /// adds the resulting passes to this pass manager.
+    ///
+    /// The text uses the syntax of the `--passes` parameter of [opt], e.g.
+    /// `instcombine,simplifycfg`. On failure, the passes preceding the
+    /// faulty element of the pipeline may already have been added.
+    ///
+    /// [PassBuilder]: crate::PassBuilder
+    /// [opt]: https://www.llvm.org/docs/CommandGuide/opt.html
+    pub fn add_pipeline_text(&mut self, text: &str) -> Result<(), PipelineParseError> {
+        if self.builder.is_null() {
+            return Err(PipelineParseError::new("no pass builder available"));
+        }
+
+        let mut message = String::new();
+        let success = unsafe {
+            super::functionPassManagerAddPipelineText(
+                self.inner,
+                self.builder,
+                text.as_ptr(),
+                text.len(),
+                (&mut message as *mut String).cast(),
+                write_error,
+            )
+        };
+
+        if success {
+            Ok(())
+        } else {
+            Err(PipelineParseError { message })
+        }
+    }
+}
+
+/// Error returned when a textual pipeline cannot be parsed.
+#[derive(Debug, Clone)]
+pub struct PipelineParseError {
+    message: String,
+}
+
+impl PipelineParseError {
+    fn new(message: &str) -> Self {
+        Self {
+            message: message.to_owned(),
+        }
+    }
+
+    /// Get the message reported by LLVM.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for PipelineParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PipelineParseError {}
+
+extern "C" fn write_error(error: *mut c_void, ptr: *const u8, len: usize) {
+    let error = unsafe { &mut *error.cast::<String>() };
+    let message = unsafe { std::slice::from_raw_parts(ptr, len) };
+    error.push_str(&String::from_utf8_lossy(message));
+}
+
+/// State of a nested pass manager being filled by a Rust closure.
+///
+/// The closure is called from C++ frames, so a panic is caught there and
+/// resumed once back on the Rust side. The partially filled manager is then
+/// not added to its parent.
+struct NestedManager<M, F> {
+    cb: Option<F>,
+    builder: *mut c_void,
+    from_raw: unsafe fn(*mut c_void, *mut c_void) -> M,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+impl<M, F> NestedManager<M, F>
+where
+    F: FnOnce(&mut M),
+{
+    fn new(
+        cb: F,
+        builder: *mut c_void,
+        from_raw: unsafe fn(*mut c_void, *mut c_void) -> M,
+    ) -> Self {
+        Self {
+            cb: Some(cb),
+            builder,
+            from_raw,
+            panic: None,
+        }
+    }
+
+    fn resume_panic(self) {
+        if let Some(payload) = self.panic {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+extern "C" fn nested_entrypoint<M, F>(nested: *mut c_void, manager: *mut c_void) -> bool
+where
+    F: FnOnce(&mut M),
+{
+    let nested = unsafe { &mut *nested.cast::<NestedManager<M, F>>() };
+    let Some(cb) = nested.cb.take() else {
+        return false;
+    };
+
+    let mut manager = unsafe { (nested.from_raw)(manager, nested.builder) };
+    match panic::catch_unwind(AssertUnwindSafe(|| cb(&mut manager))) {
+        Ok(()) => true,
+        Err(payload) => {
+            nested.panic = Some(payload);
+            false
+        }
+    }
 }
@@ -0,0 +1,129 @@
+use std::ffi::c_void;
+
+/// Read-only view of the tuning options of the default pipelines.
+///
+/// These options are set by the frontend (e.g. clang's `-fno-unroll-loops`
+/// or `-fno-vectorize`) when creating the [PassBuilder](crate::PassBuilder).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PipelineTuningOptions {
+    loop_interleaving: bool,
+    loop_vectorization: bool,
+    slp_vectorization: bool,
+    loop_unrolling: bool,
+    call_graph_profile: bool,
+    merge_functions: bool,
+}
+
+impl PipelineTuningOptions {
+    /// Whether loop interleaving is enabled.
+    pub fn loop_interleaving(&self) -> bool {
+        self.loop_interleaving
+    }
+
+    /// Whether loop vectorization is enabled.
+    pub fn loop_vectorization(&self) -> bool {
+        self.loop_vectorization
+    }
+
+    /// Whether SLP vectorization is enabled.
+    pub fn slp_vectorization(&self) -> bool {
+        self.slp_vectorization
+    }
+
+    /// Whether loop unrolling is enabled.
+    pub fn loop_unrolling(&self) -> bool {
+        self.loop_unrolling
+    }
+
+    /// Whether the call graph profile pass is enabled.
+    pub fn call_graph_profile(&self) -> bool {
+        self.call_graph_profile
+    }
+
+    /// Whether the merge functions pass is enabled.
+    #[cfg(any(
+        doc,
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+        feature = "llvm17-0",
+        feature = "llvm18-1",
+        feature = "llvm19-1",
+        feature = "llvm20-1",
+    ))]
+    pub fn merge_functions(&self) -> bool {
+        self.merge_functions
+    }
+}
+
+/// Read-only view of the profile-guided optimization options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PGOOptions {
+    action: PGOAction,
+    cs_action: CSPGOAction,
+    profile_file: String,
+}
+
+impl PGOOptions {
+    pub(crate) unsafe fn from_raw(pgo_options: *mut c_void) -> Self {
+        Self {
+            action: super::pgoOptionsGetAction(pgo_options),
+            cs_action: super::pgoOptionsGetCSAction(pgo_options),
+            profile_file: crate::target_machine::read_string(
+                pgo_options,
+                super::pgoOptionsGetProfileFile,
+            ),
+        }
+    }
+
+    /// Get the PGO action.
+    pub fn action(&self) -> PGOAction {
+        self.action
+    }
+
+    /// Get the context-sensitive PGO action.
+    pub fn cs_action(&self) -> CSPGOAction {
+        self.cs_action
+    }
+
+    /// Get the path of the profile, either generated or used.
+    ///
+    /// Returns an empty string when no path was specified.
+    pub fn profile_file(&self) -> &str {
+        &self.profile_file
+    }
+}
+
+/// Enum for the LLVM-provided PGO actions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PGOAction {
+    /// No profile is generated nor used.
+    NoAction,
+
+    /// The IR is instrumented to generate a profile.
+    IRInstr,
+
+    /// An instrumentation profile is used.
+    IRUse,
+
+    /// A sample profile is used.
+    SampleUse,
+}
+
+/// Enum for the LLVM-provided context-sensitive PGO actions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CSPGOAction {
+    /// No context-sensitive profile is generated nor used.
+    NoCSAction,
+
+    /// The IR is instrumented to generate a context-sensitive profile.
+    CSIRInstr,
+
+    /// A context-sensitive instrumentation profile is used.
+    CSIRUse,
+}
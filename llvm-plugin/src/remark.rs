@@ -0,0 +1,186 @@
+use std::ffi::c_void;
+use std::fmt;
+use std::marker::PhantomData;
+
+use inkwell::values::{AsValueRef, InstructionValue};
+
+use crate::FunctionAnalysisManager;
+
+/// Enum specifying the kind of an optimization remark.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemarkKind {
+    /// The pass applied an optimization.
+    ///
+    /// Enabled with `-pass-remarks=<pass-name>`.
+    Passed,
+
+    /// The pass missed an optimization opportunity.
+    ///
+    /// Enabled with `-pass-remarks-missed=<pass-name>`.
+    Missed,
+
+    /// Information about the decisions made by the pass.
+    ///
+    /// Enabled with `-pass-remarks-analysis=<pass-name>`.
+    Analysis,
+}
+
+/// Optimization remark, to be emitted through an [OptimizationRemarkEmitter].
+///
+/// Remarks are displayed and serialized (e.g. with `-pass-remarks-output`,
+/// or `-fsave-optimization-record` in clang) the same way as those of
+/// builtin passes.
+///
+/// # Example
+///
+/// ```
+/// # use llvm_plugin::inkwell::values::InstructionValue;
+/// # use llvm_plugin::Remark;
+/// # fn build<'ctx>(inst: InstructionValue<'ctx>) -> Remark<'ctx> {
+/// Remark::passed("my-pass", "Hoisted")
+///     .with_instruction(inst)
+///     .with_message("hoisted instruction out of loop of depth ")
+///     .with_arg("Depth", 2)
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Remark<'ctx> {
+    kind: RemarkKind,
+    pass_name: String,
+    remark_name: String,
+    instruction: Option<InstructionValue<'ctx>>,
+    args: Vec<(String, String)>,
+}
+
+impl<'ctx> Remark<'ctx> {
+    /// Creates a new remark of the given kind.
+    ///
+    /// The pass name is the one matched by the `-pass-remarks*` options,
+    /// while the remark name identifies the remark in serialized records.
+    pub fn new(
+        kind: RemarkKind,
+        pass_name: impl Into<String>,
+        remark_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            pass_name: pass_name.into(),
+            remark_name: remark_name.into(),
+            instruction: None,
+            args: Vec::new(),
+        }
+    }
+
+    /// Creates a new [RemarkKind::Passed] remark.
+    pub fn passed(pass_name: impl Into<String>, remark_name: impl Into<String>) -> Self {
+        Self::new(RemarkKind::Passed, pass_name, remark_name)
+    }
+
+    /// Creates a new [RemarkKind::Missed] remark.
+    pub fn missed(pass_name: impl Into<String>, remark_name: impl Into<String>) -> Self {
+        Self::new(RemarkKind::Missed, pass_name, remark_name)
+    }
+
+    /// Creates a new [RemarkKind::Analysis] remark.
+    pub fn analysis(pass_name: impl Into<String>, remark_name: impl Into<String>) -> Self {
+        Self::new(RemarkKind::Analysis, pass_name, remark_name)
+    }
+
+    /// Attaches the remark to the given instruction.
+    ///
+    /// The remark then uses the debug location of the instruction. Otherwise,
+    /// the remark is attached to the function it is emitted for.
+    pub fn with_instruction(mut self, instruction: InstructionValue<'ctx>) -> Self {
+        self.instruction = Some(instruction);
+        self
+    }
+
+    /// Appends some text to the remark message.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.args.push(("String".into(), message.into()));
+        self
+    }
+
+    /// Appends a structured argument to the remark.
+    ///
+    /// The value is part of the remark message, and is also serialized
+    /// along with its key in optimization records.
+    pub fn with_arg(mut self, key: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.args.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Returns the kind of the remark.
+    pub fn kind(&self) -> RemarkKind {
+        self.kind
+    }
+}
+
+#[repr(C)]
+pub(crate) struct RemarkArg {
+    key_ptr: *const u8,
+    key_len: usize,
+    value_ptr: *const u8,
+    value_len: usize,
+}
+
+/// Struct allowing to emit optimization remarks for a given function.
+///
+/// It is obtained from [FunctionAnalysisManager::get_optimization_remark_emitter].
+pub struct OptimizationRemarkEmitter<'a> {
+    inner: *mut c_void,
+    function: *mut c_void,
+    _marker: PhantomData<&'a FunctionAnalysisManager>,
+}
+
+impl OptimizationRemarkEmitter<'_> {
+    pub(crate) unsafe fn from_raw(inner: *mut c_void, function: *mut c_void) -> Self {
+        Self {
+            inner,
+            function,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns whether any remark is enabled.
+    ///
+    /// This allows skipping the computation of expensive remark arguments
+    /// when remarks are not requested.
+    pub fn is_enabled(&self) -> bool {
+        unsafe { super::optimizationRemarkEmitterIsEnabled(self.inner) }
+    }
+
+    /// Emits the given remark.
+    pub fn emit(&self, remark: &Remark<'_>) {
+        let args = remark
+            .args
+            .iter()
+            .map(|(key, value)| RemarkArg {
+                key_ptr: key.as_ptr(),
+                key_len: key.len(),
+                value_ptr: value.as_ptr(),
+                value_len: value.len(),
+            })
+            .collect::<Vec<_>>();
+
+        let instruction = remark
+            .instruction
+            .map_or(std::ptr::null_mut(), |inst| inst.as_value_ref().cast());
+
+        unsafe {
+            super::optimizationRemarkEmitterEmit(
+                self.inner,
+                self.function,
+                remark.kind,
+                remark.pass_name.as_ptr(),
+                remark.pass_name.len(),
+                remark.remark_name.as_ptr(),
+                remark.remark_name.len(),
+                instruction,
+                args.as_ptr(),
+                args.len(),
+            )
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::ffi::c_void;
+use std::ptr::NonNull;
+use std::sync::OnceLock;
+
+/// Named counter reported by LLVM statistics.
+///
+/// Statistics are printed alongside the ones of builtin passes when the
+/// `-stats` or `-stats-json` options are given to LLVM tools, and saved
+/// by clang's `-save-stats` option. Only statistics which were updated at
+/// least once are reported.
+///
+/// # Example
+///
+/// ```no_run
+/// use llvm_plugin::Statistic;
+///
+/// static NUM_REWRITES: Statistic =
+///     Statistic::new("my-pass", "NumRewrites", "Number of rewritten instructions");
+///
+/// NUM_REWRITES.inc();
+/// ```
+pub struct Statistic {
+    debug_type: &'static str,
+    name: &'static str,
+    description: &'static str,
+    inner: OnceLock<StatisticPtr>,
+}
+
+struct StatisticPtr(NonNull<c_void>);
+
+// SAFETY: the underlying `llvm::TrackingStatistic` is updated atomically.
+unsafe impl Send for StatisticPtr {}
+unsafe impl Sync for StatisticPtr {}
+
+impl Statistic {
+    /// Create a new statistic.
+    ///
+    /// The `debug_type` usually is the name of the pass updating the
+    /// statistic, and is used by LLVM to group statistics together.
+    pub const fn new(
+        debug_type: &'static str,
+        name: &'static str,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            debug_type,
+            name,
+            description,
+            inner: OnceLock::new(),
+        }
+    }
+
+    /// Increment the statistic by one.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Increment the statistic by the given value.
+    pub fn add(&self, value: u32) {
+        if value != 0 {
+            unsafe { super::statisticAdd(self.inner(), value) }
+        }
+    }
+
+    /// Set the statistic to the given value, if greater than the current one.
+    pub fn update_max(&self, value: u32) {
+        unsafe { super::statisticUpdateMax(self.inner(), value) }
+    }
+
+    /// Get the current value of the statistic.
+    pub fn value(&self) -> u32 {
+        match self.inner.get() {
+            Some(inner) => unsafe { super::statisticGetValue(inner.0.as_ptr()) },
+            None => 0,
+        }
+    }
+
+    fn inner(&self) -> *mut c_void {
+        let inner = self.inner.get_or_init(|| {
+            let stat = unsafe {
+                super::statisticNew(
+                    self.debug_type.as_ptr(),
+                    self.debug_type.len(),
+                    self.name.as_ptr(),
+                    self.name.len(),
+                    self.description.as_ptr(),
+                    self.description.len(),
+                )
+            };
+            StatisticPtr(NonNull::new(stat).expect("statistic should be allocated"))
+        });
+        inner.0.as_ptr()
+    }
+}
@@ -0,0 +1,153 @@
+use std::ffi::c_void;
+
+/// Read-only view of the target machine the pipeline is built for.
+///
+/// The view is a snapshot, taken from the [PassBuilder](crate::PassBuilder)
+/// or the pass managers it creates while building the pipeline. Running
+/// passes and analyses get the snapshot taken when they were added, from
+/// [ModuleAnalysisManager::target_machine](crate::ModuleAnalysisManager::target_machine)
+/// or [FunctionAnalysisManager::target_machine](crate::FunctionAnalysisManager::target_machine).
+///
+/// # Example
+///
+/// ```no_run
+/// # use llvm_plugin::{
+/// #     FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, PassBuilder,
+/// #     PipelineParsing, PreservedAnalyses,
+/// # };
+/// struct VectorizePass;
+///
+/// impl LlvmFunctionPass for VectorizePass {
+///     fn run_pass(
+///         &self,
+///         _function: &mut FunctionRef<'_>,
+///         manager: &FunctionAnalysisManager,
+///     ) -> PreservedAnalyses {
+///         let _has_avx2 = manager.target_machine().is_some_and(|target| {
+///             target.triple().starts_with("x86_64") && target.has_feature("avx2")
+///         });
+///         // ...
+/// #       PreservedAnalyses::All
+///     }
+/// }
+///
+/// # fn plugin_registrar(builder: &mut PassBuilder) {
+/// builder.add_function_pipeline_parsing_callback(|name, manager| {
+///     if name == "vectorize" {
+///         manager.add_pass(VectorizePass);
+///         PipelineParsing::Parsed
+///     } else {
+///         PipelineParsing::NotParsed
+///     }
+/// });
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TargetMachineInfo {
+    triple: String,
+    cpu: String,
+    features: String,
+    code_model: CodeModel,
+    data_layout: String,
+}
+
+impl TargetMachineInfo {
+    pub(crate) unsafe fn from_raw(target_machine: *mut c_void) -> Self {
+        let mut data_layout = String::new();
+        super::targetMachineGetDataLayout(
+            target_machine,
+            (&mut data_layout as *mut String).cast(),
+            write_string,
+        );
+
+        Self {
+            triple: read_string(target_machine, super::targetMachineGetTriple),
+            cpu: read_string(target_machine, super::targetMachineGetCPU),
+            features: read_string(target_machine, super::targetMachineGetFeatures),
+            code_model: super::targetMachineGetCodeModel(target_machine),
+            data_layout,
+        }
+    }
+
+    /// Get the target triple, e.g. `x86_64-unknown-linux-gnu`.
+    pub fn triple(&self) -> &str {
+        &self.triple
+    }
+
+    /// Get the target CPU, e.g. `haswell`.
+    ///
+    /// Returns an empty string when no CPU was specified.
+    pub fn cpu(&self) -> &str {
+        &self.cpu
+    }
+
+    /// Get the target feature string, e.g. `+avx2,-sse4a`.
+    pub fn features(&self) -> &str {
+        &self.features
+    }
+
+    /// Whether the given feature is explicitly enabled in the target
+    /// feature string.
+    ///
+    /// The feature is given without its `+` prefix, e.g. `avx2`. Features
+    /// implied by the target CPU are not taken into account.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features
+            .split(',')
+            .rev()
+            .find_map(|f| match f.strip_suffix(feature)? {
+                "+" => Some(true),
+                "-" => Some(false),
+                _ => None,
+            })
+            .unwrap_or(false)
+    }
+
+    /// Get the code model.
+    pub fn code_model(&self) -> CodeModel {
+        self.code_model
+    }
+
+    /// Get the string representation of the data layout.
+    pub fn data_layout(&self) -> &str {
+        &self.data_layout
+    }
+}
+
+/// Enum for the LLVM-provided code models.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeModel {
+    /// Code and data must fit in a very small address range.
+    Tiny,
+
+    /// Code and data must fit in the lower 2GB of the address space.
+    Small,
+
+    /// Code must fit in the upper 2GB of the address space.
+    Kernel,
+
+    /// Code must fit in the lower 2GB, but data is unconstrained.
+    Medium,
+
+    /// Code and data are unconstrained.
+    Large,
+}
+
+pub(crate) unsafe fn read_string(
+    target_machine: *mut c_void,
+    get: unsafe extern "C" fn(*mut c_void, *mut usize) -> *const u8,
+) -> String {
+    let mut len = 0;
+    let ptr = get(target_machine, &mut len);
+    if len == 0 {
+        return String::new();
+    }
+    String::from_utf8_lossy(std::slice::from_raw_parts(ptr, len)).into_owned()
+}
+
+extern "C" fn write_string(out: *mut c_void, ptr: *const u8, len: usize) {
+    let out = unsafe { &mut *out.cast::<String>() };
+    let data = unsafe { std::slice::from_raw_parts(ptr, len) };
+    out.push_str(&String::from_utf8_lossy(data));
+}
@@ -0,0 +1,75 @@
+use std::marker::PhantomData;
+
+/// Scope recorded by LLVM's time trace profiler.
+///
+/// Time traces are enabled with clang's `-ftime-trace` option, or opt's
+/// `-time-trace` option, and produce a Chrome JSON trace. Every invocation
+/// of a Rust pass is recorded under the pass [name], and every run of a
+/// Rust analysis under its type name, with the name of the IR unit as
+/// detail. Scopes allow recording finer grained parts of a pass, and are
+/// closed when dropped.
+///
+/// Creating a scope is cheap when the profiler is disabled.
+///
+/// # Example
+///
+/// ```no_run
+/// use llvm_plugin::TimeTraceScope;
+///
+/// # let function_name = "";
+/// let _scope = TimeTraceScope::with_detail("MyPassRewrite", || function_name.to_owned());
+/// // perform the rewrite
+/// ```
+///
+/// [name]: crate::LlvmModulePass::name
+pub struct TimeTraceScope {
+    active: bool,
+    // the profiler instance is thread local
+    _marker: PhantomData<*const ()>,
+}
+
+impl TimeTraceScope {
+    /// Open a new scope with the given name.
+    pub fn new(name: &str) -> Self {
+        Self::with_detail(name, String::new)
+    }
+
+    /// Open a new scope with the given name and detail.
+    ///
+    /// The detail is only computed if the profiler is enabled.
+    pub fn with_detail<F>(name: &str, detail: F) -> Self
+    where
+        F: FnOnce() -> String,
+    {
+        let active = Self::is_enabled();
+        if active {
+            let detail = detail();
+            unsafe {
+                super::timeTraceProfilerBegin(
+                    name.as_ptr(),
+                    name.len(),
+                    detail.as_ptr(),
+                    detail.len(),
+                )
+            }
+        }
+
+        Self {
+            active,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Whether the time trace profiler is enabled on the current thread.
+    pub fn is_enabled() -> bool {
+        unsafe { super::timeTraceProfilerIsEnabled() }
+    }
+}
+
+impl Drop for TimeTraceScope {
+    fn drop(&mut self) {
+        if self.active {
+            unsafe { super::timeTraceProfilerEnd() }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::any::Any;
+use std::ffi::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::OnceLock;
+
+use crate::{DiagnosticSeverity, Diagnostics, PreservedAnalyses};
+
+static PLUGIN_NAME: OnceLock<&'static str> = OnceLock::new();
+
+pub(crate) fn set_plugin_name(name: &'static str) {
+    let _ = PLUGIN_NAME.set(name);
+}
+
+/// Runs the given closure, turning a panic into an LLVM fatal error.
+///
+/// Unwinding through the C++ frames of the LLVM tool is undefined behavior,
+/// so every `extern "C"` function given to LLVM must go through here.
+pub(crate) fn catch_unwind<R>(kind: &str, type_name: &str, f: impl FnOnce() -> R) -> R {
+    match try_catch_unwind(kind, type_name, f) {
+        Ok(res) => res,
+        Err(msg) => report_fatal_error(&msg),
+    }
+}
+
+/// Runs the given pass closure, turning a panic into either an LLVM fatal
+/// error, or a warning emitted through the given `LLVMContext`.
+///
+/// In the latter case, the pass may have left the IR partially modified,
+/// so all analyses are considered invalidated.
+pub(crate) fn catch_pass_unwind(
+    kind: &str,
+    type_name: &str,
+    abort_on_panic: bool,
+    context: *mut c_void,
+    f: impl FnOnce() -> PreservedAnalyses,
+) -> PreservedAnalyses {
+    match try_catch_unwind(kind, type_name, f) {
+        Ok(preserve) => preserve,
+        Err(msg) if abort_on_panic => report_fatal_error(&msg),
+        Err(msg) => {
+            let diagnostics = unsafe { Diagnostics::from_context(context) };
+            diagnostics.emit(DiagnosticSeverity::Warning, &msg, None);
+            PreservedAnalyses::None
+        }
+    }
+}
+
+fn try_catch_unwind<R>(kind: &str, type_name: &str, f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| panic_report(kind, type_name, payload.as_ref()))
+}
+
+fn report_fatal_error(msg: &str) -> ! {
+    unsafe { super::reportFatalError(msg.as_ptr(), msg.len()) }
+}
+
+fn panic_report(kind: &str, type_name: &str, payload: &(dyn Any + Send)) -> String {
+    let plugin = PLUGIN_NAME.get().copied().unwrap_or("<unknown>");
+    let msg = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+
+    format!("plugin `{plugin}` panicked in {kind} `{type_name}`: {msg}")
+}
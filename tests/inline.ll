@@ -0,0 +1,21 @@
+define internal i32 @add_one(i32 %x) {
+    %res = add i32 %x, 1
+    ret i32 %res
+}
+
+define internal i32 @add_two(i32 %x) {
+    %res = add i32 %x, 2
+    ret i32 %res
+}
+
+define internal i32 @add_three(i32 %x) noinline {
+    %res = add i32 %x, 3
+    ret i32 %res
+}
+
+define i32 @main(i32 %argc) {
+    %a = call i32 @add_one(i32 %argc)
+    %b = call i32 @add_two(i32 %a)
+    %c = call i32 @add_three(i32 %b)
+    ret i32 %c
+}
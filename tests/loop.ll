@@ -0,0 +1,32 @@
+define void @nest(i64 %nx, i64 %ny) {
+entry:
+    br label %for.outer
+
+for.outer:
+    %i = phi i64 [ 0, %entry ], [ %inc.i, %for.outer.latch ]
+    %cmp.ny = icmp slt i64 0, %ny
+    br i1 %cmp.ny, label %for.inner.preheader, label %for.outer.latch
+
+for.inner.preheader:
+    br label %for.inner
+
+for.inner:
+    %j = phi i64 [ 0, %for.inner.preheader ], [ %inc.j, %for.inner.latch ]
+    br label %for.inner.latch
+
+for.inner.latch:
+    %inc.j = add nsw i64 %j, 1
+    %cmp.j = icmp slt i64 %inc.j, %ny
+    br i1 %cmp.j, label %for.inner, label %for.inner.exit
+
+for.inner.exit:
+    br label %for.outer.latch
+
+for.outer.latch:
+    %inc.i = add nsw i64 %i, 1
+    %cmp.i = icmp slt i64 %inc.i, %nx
+    br i1 %cmp.i, label %for.outer, label %for.outer.exit
+
+for.outer.exit:
+    ret void
+}
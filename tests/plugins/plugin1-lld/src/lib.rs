@@ -1,6 +1,6 @@
-use llvm_plugin::inkwell::module::Module;
 use llvm_plugin::{
-    LlvmModulePass, ModuleAnalysisManager, PassBuilder, PipelineParsing, PreservedAnalyses,
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
 };
 
 #[llvm_plugin::plugin(name = "pass_plugin", version = "0.1")]
@@ -19,7 +19,11 @@ static mut CALL_COUNT: u32 = 0;
 
 struct Pass;
 impl LlvmModulePass for Pass {
-    fn run_pass(&self, module: &mut Module, _manager: &ModuleAnalysisManager) -> PreservedAnalyses {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
         if matches!(
             module.get_source_file_name().to_str(),
             Ok(s) if s.contains("build_script_build")
@@ -1,13 +1,12 @@
 use llvm_plugin::inkwell::basic_block::BasicBlock;
-use llvm_plugin::inkwell::module::Module;
 use llvm_plugin::inkwell::support::LLVMString;
 use llvm_plugin::inkwell::values::{
-    AnyValue, BasicValueEnum, FunctionValue, GlobalValue, InstructionOpcode, InstructionValue,
+    AnyValue, BasicValueEnum, GlobalValue, InstructionOpcode, InstructionValue,
 };
 use llvm_plugin::{
-    AnalysisKey, FunctionAnalysisManager, LlvmFunctionAnalysis, LlvmFunctionPass,
-    LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, PassBuilder, PipelineParsing,
-    PreservedAnalyses,
+    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis, LlvmFunctionPass,
+    LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder,
+    PipelineParsing, PreservedAnalyses,
 };
 
 #[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
@@ -41,7 +40,11 @@ fn plugin_registrar(builder: &mut PassBuilder) {
 
 struct Pass1;
 impl LlvmModulePass for Pass1 {
-    fn run_pass(&self, module: &mut Module, manager: &ModuleAnalysisManager) -> PreservedAnalyses {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
         let result = manager
             .get_result::<Ana1>(module)
             .as_ref()
@@ -55,7 +58,7 @@ struct Pass2;
 impl LlvmFunctionPass for Pass2 {
     fn run_pass(
         &self,
-        function: &mut FunctionValue,
+        function: &mut FunctionRef,
         manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses {
         let result = manager.get_result::<Ana2>(function).expect("get_result");
@@ -72,7 +75,7 @@ struct Ana1;
 impl LlvmModuleAnalysis for Ana1 {
     type Result = Option<LLVMString>;
 
-    fn run_analysis(&self, module: &Module, _manager: &ModuleAnalysisManager) -> Self::Result {
+    fn run_analysis(&self, module: &ModuleRef, _manager: &ModuleAnalysisManager) -> Self::Result {
         unsafe { ANA1_CALL_COUNT += 1 };
         module
             .get_first_global()
@@ -97,7 +100,7 @@ impl LlvmFunctionAnalysis for Ana2 {
 
     fn run_analysis(
         &self,
-        function: &FunctionValue,
+        function: &FunctionRef,
         _manager: &FunctionAnalysisManager,
     ) -> Self::Result {
         unsafe { ANA2_CALL_COUNT += 1 };
@@ -0,0 +1,61 @@
+use llvm_plugin::{
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, PassBuilder, PipelineParsing,
+    PreservedAnalyses, Remark,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "fpass" {
+            manager.add_pass(RemarkPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static mut REMARK_PASS_CALLED: u32 = 0;
+
+struct RemarkPass;
+impl LlvmFunctionPass for RemarkPass {
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef,
+        manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        let emitter = manager.get_optimization_remark_emitter(function);
+        assert!(emitter.is_enabled());
+
+        let count = function
+            .get_basic_blocks()
+            .iter()
+            .map(|bb| bb.get_instructions().count())
+            .sum::<usize>();
+        emitter.emit(
+            &Remark::analysis("fpass", "InstructionCount")
+                .with_message("instructions: ")
+                .with_arg("NumInstructions", count),
+        );
+
+        if let Some(inst) = function
+            .get_first_basic_block()
+            .and_then(|bb| bb.get_first_instruction())
+        {
+            emitter.emit(
+                &Remark::missed("fpass", "FirstInstruction")
+                    .with_instruction(inst)
+                    .with_message("nothing to do"),
+            );
+        }
+
+        unsafe { REMARK_PASS_CALLED += 1 };
+        PreservedAnalyses::All
+    }
+}
+
+impl Drop for RemarkPass {
+    fn drop(&mut self) {
+        assert!(unsafe { REMARK_PASS_CALLED } > 0);
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_pass(ModulePass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    #[cfg(not(feature = "llvm11-0"))]
+    {
+        use llvm_plugin::IrUnit;
+
+        builder.add_before_pass_callback(|name, unit| {
+            if name == "plugin11-mpass" {
+                assert!(matches!(unit, IrUnit::Module(_)));
+                BEFORE_MPASS.fetch_add(1, Ordering::SeqCst);
+            }
+            if name.contains("InstCombine") {
+                assert!(matches!(unit, IrUnit::Function(_)));
+                BEFORE_INSTCOMBINE.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        builder.add_after_pass_callback(|name, unit, _| {
+            if name.contains("InstCombine") {
+                assert!(matches!(unit, IrUnit::Function(_)));
+                AFTER_INSTCOMBINE.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        builder.add_before_analysis_callback(|_, _| {
+            ANALYSES.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+}
+
+static BEFORE_INSTCOMBINE: AtomicU32 = AtomicU32::new(0);
+static AFTER_INSTCOMBINE: AtomicU32 = AtomicU32::new(0);
+static ANALYSES: AtomicU32 = AtomicU32::new(0);
+static BEFORE_MPASS: AtomicU32 = AtomicU32::new(0);
+
+struct ModulePass;
+impl LlvmModulePass for ModulePass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        #[cfg(not(feature = "llvm11-0"))]
+        {
+            assert!(BEFORE_INSTCOMBINE.load(Ordering::SeqCst) > 0);
+            assert_eq!(
+                BEFORE_INSTCOMBINE.load(Ordering::SeqCst),
+                AFTER_INSTCOMBINE.load(Ordering::SeqCst)
+            );
+            assert!(ANALYSES.load(Ordering::SeqCst) > 0);
+            assert_eq!(BEFORE_MPASS.load(Ordering::SeqCst), 1);
+        }
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        "plugin11-mpass"
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, LlvmModulePass, ModuleAnalysisManager,
+    ModuleRef, PassBuilder, PipelineParsing, PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| match name {
+        "required-fpass" => {
+            manager.add_pass(RequiredPass);
+            PipelineParsing::Parsed
+        }
+        "optional-fpass" => {
+            manager.add_pass(OptionalPass);
+            PipelineParsing::Parsed
+        }
+        _ => PipelineParsing::NotParsed,
+    });
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_pass(ModulePass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    #[cfg(not(feature = "llvm11-0"))]
+    {
+        use llvm_plugin::IrUnit;
+
+        builder.add_should_run_optional_pass_callback(|name, unit| {
+            assert_ne!(name, "plugin12-required");
+            if name.contains("InstCombine") && matches!(unit, IrUnit::Function(_)) {
+                SKIPPED.fetch_add(1, Ordering::SeqCst);
+                return false;
+            }
+            name != "plugin12-optional"
+        });
+
+        builder.add_after_pass_callback(|name, _, _| {
+            assert!(!name.contains("InstCombine"));
+        });
+    }
+}
+
+static SKIPPED: AtomicU32 = AtomicU32::new(0);
+static REQUIRED_PASS_CALLED: AtomicU32 = AtomicU32::new(0);
+
+struct RequiredPass;
+impl LlvmFunctionPass for RequiredPass {
+    const REQUIRED: bool = true;
+
+    fn run_pass(
+        &self,
+        _function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        REQUIRED_PASS_CALLED.fetch_add(1, Ordering::SeqCst);
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        "plugin12-required"
+    }
+}
+
+struct OptionalPass;
+impl LlvmFunctionPass for OptionalPass {
+    fn run_pass(
+        &self,
+        _function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        assert!(
+            cfg!(feature = "llvm11-0"),
+            "optional pass should have been skipped"
+        );
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        "plugin12-optional"
+    }
+}
+
+struct ModulePass;
+impl LlvmModulePass for ModulePass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        #[cfg(not(feature = "llvm11-0"))]
+        assert!(SKIPPED.load(Ordering::SeqCst) > 0);
+        assert!(REQUIRED_PASS_CALLED.load(Ordering::SeqCst) > 0);
+        PreservedAnalyses::All
+    }
+}
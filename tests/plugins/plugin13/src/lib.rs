@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, LlvmModulePass, ModuleAnalysisManager,
+    ModuleRef, PassBuilder, PipelineParsing, PreservedAnalyses,
+};
+
+llvm_plugin::debug_counter!(
+    static REWRITE_COUNTER = "plugin13-rewrite", "Controls rewrites of plugin13"
+);
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "fpass" {
+            manager.add_pass(FunctionPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_pass(ModulePass);
+            PipelineParsing::Parsed
+        } else if name == "counter-test" {
+            manager.add_pass(CounterPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static FUNCTION_PASS_CALLED: AtomicU32 = AtomicU32::new(0);
+
+struct FunctionPass;
+impl LlvmFunctionPass for FunctionPass {
+    fn run_pass(
+        &self,
+        _function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        FUNCTION_PASS_CALLED.fetch_add(1, Ordering::SeqCst);
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        "plugin13-fpass"
+    }
+}
+
+struct ModulePass;
+impl LlvmModulePass for ModulePass {
+    const REQUIRED: bool = true;
+
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        // `-opt-bisect-limit=0` skips every optional pass
+        #[cfg(not(feature = "llvm11-0"))]
+        assert_eq!(FUNCTION_PASS_CALLED.load(Ordering::SeqCst), 0);
+
+        // no `-debug-counter` given
+        assert_eq!(REWRITE_COUNTER.name(), "plugin13-rewrite");
+        assert!(REWRITE_COUNTER.should_execute());
+        PreservedAnalyses::All
+    }
+}
+
+struct CounterPass;
+impl LlvmModulePass for CounterPass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        // `-debug-counter` only lets the 2nd call through
+        let executed = [(); 3].map(|_| REWRITE_COUNTER.should_execute());
+        assert_eq!(executed, [false, true, false]);
+        PreservedAnalyses::All
+    }
+}
@@ -0,0 +1,65 @@
+use llvm_plugin::{
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, LlvmModulePass, ModuleAnalysisManager,
+    ModuleRef, PassBuilder, PipelineParsing, PreservedAnalyses, Statistic,
+};
+
+static NUM_FUNCTIONS: Statistic =
+    Statistic::new("plugin14", "NumFunctions", "Number of visited functions");
+static MAX_BLOCKS: Statistic = Statistic::new(
+    "plugin14",
+    "MaxBlocks",
+    "Maximum number of blocks per function",
+);
+static NUM_UNUSED: Statistic = Statistic::new("plugin14", "NumUnused", "Never updated");
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "fpass" {
+            manager.add_pass(FunctionPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_pass(ModulePass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+struct FunctionPass;
+impl LlvmFunctionPass for FunctionPass {
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        NUM_FUNCTIONS.inc();
+        MAX_BLOCKS.update_max(function.count_basic_blocks());
+        PreservedAnalyses::All
+    }
+}
+
+struct ModulePass;
+impl LlvmModulePass for ModulePass {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        let num_functions = module
+            .get_functions()
+            .filter(|f| f.count_basic_blocks() > 0)
+            .count();
+        assert_eq!(NUM_FUNCTIONS.value() as usize, num_functions);
+        assert!(MAX_BLOCKS.value() > 0);
+        assert_eq!(NUM_UNUSED.value(), 0);
+        PreservedAnalyses::All
+    }
+}
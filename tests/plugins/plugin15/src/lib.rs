@@ -0,0 +1,63 @@
+use llvm_plugin::{
+    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis, LlvmFunctionPass,
+    PassBuilder, PipelineParsing, PreservedAnalyses, TimeTraceScope,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "fpass" {
+            manager.add_pass(FunctionPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_function_analysis_registration_callback(|manager| {
+        manager.register_pass(FunctionAnalysis);
+    });
+}
+
+struct FunctionAnalysis;
+impl LlvmFunctionAnalysis for FunctionAnalysis {
+    type Result = ();
+
+    fn run_analysis(
+        &self,
+        _function: &FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> Self::Result {
+    }
+
+    fn id() -> AnalysisKey {
+        static ID: u8 = 0;
+        &ID
+    }
+}
+
+struct FunctionPass;
+impl LlvmFunctionPass for FunctionPass {
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef,
+        manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        // opt is given `-time-trace`
+        assert!(TimeTraceScope::is_enabled());
+
+        manager.get_result::<FunctionAnalysis>(function);
+
+        let _outer = TimeTraceScope::new("plugin15-outer");
+        for bb in function.get_basic_blocks() {
+            let _inner = TimeTraceScope::with_detail("plugin15-inner", || {
+                bb.get_name().to_string_lossy().into_owned()
+            });
+        }
+        PreservedAnalyses::All
+    }
+
+    fn name(&self) -> &str {
+        "plugin15-fpass"
+    }
+}
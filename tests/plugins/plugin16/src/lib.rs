@@ -0,0 +1,32 @@
+use llvm_plugin::{PassBuilder, PipelineParsing};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager
+                .add_pipeline_text("function(instcombine),globaldce")
+                .expect("module pipeline should parse");
+
+            let err = manager
+                .add_pipeline_text("not-a-pass")
+                .expect_err("unknown pass should not parse");
+            assert!(!err.message().is_empty());
+
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "fpass" {
+            manager
+                .add_pipeline_text("instcombine,simplifycfg")
+                .expect("function pipeline should parse");
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
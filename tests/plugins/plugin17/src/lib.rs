@@ -0,0 +1,61 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, LlvmModulePass, ModuleAnalysisManager,
+    ModuleRef, PassBuilder, PipelineParsing, PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_function_pass_manager(|manager| {
+                manager.add_pass(FunctionPass);
+            });
+
+            // a nested manager whose callback panics is not added
+            let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                manager.add_function_pass_manager(|manager| {
+                    manager.add_pass(FunctionPass);
+                    panic!("expected panic");
+                });
+            }));
+            assert!(res.is_err());
+
+            manager.add_module_pass_manager(|manager| {
+                manager.add_pass(ModulePass);
+            });
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static FUNCTION_PASS_CALLED: AtomicU32 = AtomicU32::new(0);
+
+struct FunctionPass;
+impl LlvmFunctionPass for FunctionPass {
+    fn run_pass(
+        &self,
+        _function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        FUNCTION_PASS_CALLED.fetch_add(1, Ordering::SeqCst);
+        PreservedAnalyses::All
+    }
+}
+
+struct ModulePass;
+impl LlvmModulePass for ModulePass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        // only run on `main`, by the first nested manager
+        assert_eq!(FUNCTION_PASS_CALLED.load(Ordering::SeqCst), 1);
+        PreservedAnalyses::All
+    }
+}
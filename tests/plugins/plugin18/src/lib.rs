@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, OptimizationLevel, PassBuilder,
+    PipelineParsing, PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_module_pipeline_parsing_callback(|name, manager| match name {
+        "my-O2-plus" => {
+            manager.add_pass(BeforePass);
+            manager.add_per_module_default_pipeline(OptimizationLevel::O2);
+            manager.add_pass(AfterPass);
+            PipelineParsing::Parsed
+        }
+        "my-O1-optimization" => {
+            manager.add_module_optimization_pipeline(OptimizationLevel::O1);
+            PipelineParsing::Parsed
+        }
+        "my-O0" => {
+            manager.add_per_module_default_pipeline(OptimizationLevel::O0);
+            manager.add_thin_lto_pre_link_default_pipeline(OptimizationLevel::O0);
+            manager.add_lto_pre_link_default_pipeline(OptimizationLevel::O0);
+            PipelineParsing::Parsed
+        }
+        "my-pre-link" => {
+            manager.add_thin_lto_pre_link_default_pipeline(OptimizationLevel::Os);
+            manager.add_lto_pre_link_default_pipeline(OptimizationLevel::Oz);
+            PipelineParsing::Parsed
+        }
+        _ => PipelineParsing::NotParsed,
+    });
+
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "my-O3-simplification" {
+            manager.add_function_simplification_pipeline(OptimizationLevel::O3);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static BEFORE_PASS_CALLED: AtomicU32 = AtomicU32::new(0);
+
+struct BeforePass;
+impl LlvmModulePass for BeforePass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        BEFORE_PASS_CALLED.fetch_add(1, Ordering::SeqCst);
+        PreservedAnalyses::All
+    }
+}
+
+struct AfterPass;
+impl LlvmModulePass for AfterPass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        assert_eq!(BEFORE_PASS_CALLED.load(Ordering::SeqCst), 1);
+        PreservedAnalyses::All
+    }
+}
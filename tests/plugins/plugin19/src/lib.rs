@@ -0,0 +1,40 @@
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, OptimizationLevel, PassBuilder,
+    PipelineParsing, PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_top_level_pipeline_parsing_callback(|manager, pipeline| {
+        let [element] = pipeline else {
+            return PipelineParsing::NotParsed;
+        };
+        if element.name() != "secure" {
+            return PipelineParsing::NotParsed;
+        }
+
+        let inner = element.inner_pipeline();
+        let names = inner.iter().map(|e| e.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["default<O2>", "function"]);
+
+        let nested = inner[1].inner_pipeline();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].name(), "instcombine");
+        assert!(nested[0].inner_pipeline().is_empty());
+
+        manager.add_pass(SecurePass);
+        manager.add_per_module_default_pipeline(OptimizationLevel::O2);
+        PipelineParsing::Parsed
+    });
+}
+
+struct SecurePass;
+impl LlvmModulePass for SecurePass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        PreservedAnalyses::All
+    }
+}
@@ -1,8 +1,7 @@
-use llvm_plugin::inkwell::module::Module;
 use llvm_plugin::inkwell::support::LLVMString;
 use llvm_plugin::inkwell::values::{AnyValue, BasicValueEnum, GlobalValue};
 use llvm_plugin::{
-    AnalysisKey, LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, PassBuilder,
+    AnalysisKey, LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder,
     PipelineParsing, PreservedAnalyses,
 };
 
@@ -27,7 +26,11 @@ fn plugin_registrar(builder: &mut PassBuilder) {
 
 struct Pass1;
 impl LlvmModulePass for Pass1 {
-    fn run_pass(&self, module: &mut Module, manager: &ModuleAnalysisManager) -> PreservedAnalyses {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
         let result = manager
             .get_result::<Ana1>(module)
             .as_ref()
@@ -39,7 +42,11 @@ impl LlvmModulePass for Pass1 {
 
 struct Pass2;
 impl LlvmModulePass for Pass2 {
-    fn run_pass(&self, module: &mut Module, manager: &ModuleAnalysisManager) -> PreservedAnalyses {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
         let result = manager
             .get_result::<Ana1>(module)
             .as_ref()
@@ -56,7 +63,7 @@ struct Ana1;
 impl LlvmModuleAnalysis for Ana1 {
     type Result = Option<LLVMString>;
 
-    fn run_analysis(&self, module: &Module, _manager: &ModuleAnalysisManager) -> Self::Result {
+    fn run_analysis(&self, module: &ModuleRef, _manager: &ModuleAnalysisManager) -> Self::Result {
         unsafe { ANA1_CALL_COUNT += 1 };
         module
             .get_first_global()
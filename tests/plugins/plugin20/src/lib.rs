@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis, LlvmModuleAnalysis,
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_pass(ModulePass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_module_analysis_registration_callback(|manager| {
+        manager.register_printable_pass(ModuleAnalysis);
+    });
+
+    builder.add_function_analysis_registration_callback(|manager| {
+        manager.register_pass(FunctionAnalysis);
+    });
+}
+
+static MODULE_ANALYSIS_CALLED: AtomicU32 = AtomicU32::new(0);
+static FUNCTION_ANALYSIS_CALLED: AtomicU32 = AtomicU32::new(0);
+
+struct ModuleAnalysis;
+impl LlvmModuleAnalysis for ModuleAnalysis {
+    type Result = Vec<String>;
+
+    fn run_analysis(&self, module: &ModuleRef, _manager: &ModuleAnalysisManager) -> Self::Result {
+        MODULE_ANALYSIS_CALLED.fetch_add(1, Ordering::SeqCst);
+        module
+            .get_functions()
+            .map(|f| f.get_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn id() -> AnalysisKey {
+        static ID: u8 = 0;
+        &ID
+    }
+
+    const PIPELINE_NAME: Option<&'static str> = Some("plugin20-manalysis");
+}
+
+struct FunctionAnalysis;
+impl LlvmFunctionAnalysis for FunctionAnalysis {
+    type Result = u32;
+
+    fn run_analysis(
+        &self,
+        function: &FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> Self::Result {
+        FUNCTION_ANALYSIS_CALLED.fetch_add(1, Ordering::SeqCst);
+        function.count_basic_blocks()
+    }
+
+    fn id() -> AnalysisKey {
+        static ID: u8 = 0;
+        &ID
+    }
+
+    const PIPELINE_NAME: Option<&'static str> = Some("plugin20-fanalysis");
+}
+
+struct ModulePass;
+impl LlvmModulePass for ModulePass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        // computed by `require`, then again after `invalidate`
+        assert_eq!(MODULE_ANALYSIS_CALLED.load(Ordering::SeqCst), 2);
+        assert!(FUNCTION_ANALYSIS_CALLED.load(Ordering::SeqCst) > 0);
+        PreservedAnalyses::All
+    }
+}
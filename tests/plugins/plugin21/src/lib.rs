@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    #[cfg(not(any(
+        feature = "llvm11-0",
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+    )))]
+    {
+        builder.set_inline_advisor(advisor::ReplacedAdvisor);
+        builder.set_inline_advisor(advisor::Advisor);
+    }
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "check-inline" {
+            manager.add_pass(CheckInlinePass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static ADVISOR_CALLED: AtomicU32 = AtomicU32::new(0);
+
+#[cfg(not(any(
+    feature = "llvm11-0",
+    feature = "llvm12-0",
+    feature = "llvm13-0",
+    feature = "llvm14-0",
+    feature = "llvm15-0",
+)))]
+mod advisor {
+    use std::sync::atomic::Ordering;
+
+    use llvm_plugin::{InlineAdvice, InlineCallSite, LlvmInlineAdvisor};
+
+    pub struct ReplacedAdvisor;
+    impl LlvmInlineAdvisor for ReplacedAdvisor {
+        fn advise(&self, _call_site: &InlineCallSite<'_>) -> InlineAdvice {
+            panic!("replaced advisor should not be called");
+        }
+    }
+
+    pub struct Advisor;
+    impl LlvmInlineAdvisor for Advisor {
+        fn advise(&self, call_site: &InlineCallSite<'_>) -> InlineAdvice {
+            super::ADVISOR_CALLED.fetch_add(1, Ordering::SeqCst);
+
+            assert_eq!(call_site.caller().get_name().to_bytes(), b"main");
+            assert!(call_site.default_cost().is_profitable());
+
+            if call_site.callee().get_name().to_bytes() == b"add_one" {
+                InlineAdvice::inline()
+            } else {
+                InlineAdvice::no_inline().with_reason("only add_one is inlined")
+            }
+        }
+    }
+}
+
+struct CheckInlinePass;
+impl LlvmModulePass for CheckInlinePass {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        #[cfg(not(any(
+            feature = "llvm11-0",
+            feature = "llvm12-0",
+            feature = "llvm13-0",
+            feature = "llvm14-0",
+            feature = "llvm15-0",
+        )))]
+        {
+            assert_eq!(ADVISOR_CALLED.load(Ordering::SeqCst), 2);
+            assert!(module.get_function("add_one").is_none());
+            assert!(module.get_function("add_two").is_some());
+            assert!(module.get_function("add_three").is_some());
+        }
+
+        #[cfg(any(
+            feature = "llvm11-0",
+            feature = "llvm12-0",
+            feature = "llvm13-0",
+            feature = "llvm14-0",
+            feature = "llvm15-0",
+        ))]
+        {
+            assert_eq!(ADVISOR_CALLED.load(Ordering::SeqCst), 0);
+            assert!(module.get_function("main").is_some());
+        }
+
+        PreservedAnalyses::All
+    }
+}
@@ -0,0 +1,121 @@
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    #[cfg(not(any(
+        feature = "llvm11-0",
+        feature = "llvm12-0",
+        feature = "llvm13-0",
+        feature = "llvm14-0",
+        feature = "llvm15-0",
+        feature = "llvm16-0",
+    )))]
+    {
+        builder.set_inline_order(order::ReplacedOrder);
+        builder.set_inline_order(order::Order);
+        builder.set_inline_advisor(order::Advisor);
+    }
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "inline-order-test" {
+            #[cfg(not(any(
+                feature = "llvm11-0",
+                feature = "llvm12-0",
+                feature = "llvm13-0",
+                feature = "llvm14-0",
+                feature = "llvm15-0",
+                feature = "llvm16-0",
+            )))]
+            manager.add_pipeline_text("module-inline").unwrap();
+
+            manager.add_pass(CheckInlineOrderPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+#[cfg(not(any(
+    feature = "llvm11-0",
+    feature = "llvm12-0",
+    feature = "llvm13-0",
+    feature = "llvm14-0",
+    feature = "llvm15-0",
+    feature = "llvm16-0",
+)))]
+mod order {
+    use std::sync::Mutex;
+
+    use llvm_plugin::{InlineAdvice, InlineCallSite, LlvmInlineAdvisor, LlvmInlineOrder};
+
+    pub static ADVISED_CALLEES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    pub struct ReplacedOrder;
+    impl LlvmInlineOrder for ReplacedOrder {
+        fn priority(&self, _call_site: &InlineCallSite<'_>) -> i64 {
+            panic!("replaced inline order should not be called");
+        }
+    }
+
+    pub struct Order;
+    impl LlvmInlineOrder for Order {
+        fn priority(&self, call_site: &InlineCallSite<'_>) -> i64 {
+            match call_site.callee().get_name().to_bytes() {
+                b"add_two" => 2,
+                b"add_one" => 1,
+                _ => 0,
+            }
+        }
+    }
+
+    pub struct Advisor;
+    impl LlvmInlineAdvisor for Advisor {
+        fn advise(&self, call_site: &InlineCallSite<'_>) -> InlineAdvice {
+            let callee = call_site.callee();
+            let name = callee.get_name().to_string_lossy().into_owned();
+            ADVISED_CALLEES.lock().unwrap().push(name);
+            InlineAdvice::inline()
+        }
+    }
+}
+
+struct CheckInlineOrderPass;
+impl LlvmModulePass for CheckInlineOrderPass {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        #[cfg(not(any(
+            feature = "llvm11-0",
+            feature = "llvm12-0",
+            feature = "llvm13-0",
+            feature = "llvm14-0",
+            feature = "llvm15-0",
+            feature = "llvm16-0",
+        )))]
+        {
+            let callees = order::ADVISED_CALLEES.lock().unwrap();
+            assert_eq!(*callees, ["add_two", "add_one"]);
+            assert!(module.get_function("add_one").is_none());
+            assert!(module.get_function("add_two").is_none());
+            assert!(module.get_function("add_three").is_some());
+        }
+
+        #[cfg(any(
+            feature = "llvm11-0",
+            feature = "llvm12-0",
+            feature = "llvm13-0",
+            feature = "llvm14-0",
+            feature = "llvm15-0",
+            feature = "llvm16-0",
+        ))]
+        assert!(module.get_function("main").is_some());
+
+        PreservedAnalyses::All
+    }
+}
@@ -0,0 +1,70 @@
+use llvm_plugin::{
+    AnalysisKey, CodeModel, LlvmModuleAnalysis, LlvmModulePass, ModuleAnalysisManager, ModuleRef,
+    PassBuilder, PipelineParsing, PreservedAnalyses, TargetMachineInfo,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    let target = builder
+        .target_machine()
+        .expect("target machine should be set");
+    check_target(Some(&target));
+
+    builder.add_module_analysis_registration_callback(|manager| {
+        check_target(manager.target_machine());
+        manager.register_pass(TargetAnalysis);
+    });
+
+    builder.add_module_pipeline_parsing_callback(move |name, manager| {
+        if name == "check-target" {
+            assert_eq!(manager.target_machine().as_ref(), Some(&target));
+            manager.add_pass(CheckTargetPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+fn check_target(target: Option<&TargetMachineInfo>) {
+    let target = target.expect("target machine should be set");
+    assert_eq!(target.triple(), "x86_64-unknown-linux-gnu");
+    assert_eq!(target.cpu(), "haswell");
+    assert!(target.has_feature("avx2"));
+    assert!(!target.has_feature("sse4a"));
+    assert_eq!(target.code_model(), CodeModel::Small);
+    assert!(target.data_layout().starts_with("e-m:e"));
+}
+
+struct CheckTargetPass;
+
+impl LlvmModulePass for CheckTargetPass {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        check_target(manager.target_machine());
+        manager.get_result::<TargetAnalysis>(module);
+
+        let proxy = manager.get_function_analysis_manager_proxy(module);
+        check_target(proxy.get_manager().target_machine());
+
+        PreservedAnalyses::All
+    }
+}
+
+struct TargetAnalysis;
+
+impl LlvmModuleAnalysis for TargetAnalysis {
+    type Result = ();
+
+    fn run_analysis(&self, _module: &ModuleRef, manager: &ModuleAnalysisManager) -> Self::Result {
+        check_target(manager.target_machine());
+    }
+
+    fn id() -> AnalysisKey {
+        static ID: u8 = 0;
+        &ID
+    }
+}
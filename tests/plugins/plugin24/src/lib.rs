@@ -0,0 +1,48 @@
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PGOAction, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    let tuning = builder.pipeline_tuning_options();
+    assert!(tuning.loop_interleaving());
+    assert!(tuning.loop_vectorization());
+    assert!(!tuning.loop_unrolling());
+
+    let pgo = builder.pgo_options().expect("PGO options should be set");
+    assert_eq!(pgo.action(), PGOAction::IRInstr);
+    assert_eq!(pgo.profile_file(), "plugin24.profraw");
+
+    builder.add_module_pipeline_parsing_callback(move |name, manager| {
+        if name == "check-options" {
+            manager.add_pass(CheckOptionsPass {
+                loop_vectorization: tuning.loop_vectorization(),
+                loop_unrolling: tuning.loop_unrolling(),
+                profile_file: pgo.profile_file().to_owned(),
+            });
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+struct CheckOptionsPass {
+    loop_vectorization: bool,
+    loop_unrolling: bool,
+    profile_file: String,
+}
+
+impl LlvmModulePass for CheckOptionsPass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        assert!(self.loop_vectorization);
+        assert!(!self.loop_unrolling);
+        assert_eq!(self.profile_file, "plugin24.profraw");
+        PreservedAnalyses::All
+    }
+}
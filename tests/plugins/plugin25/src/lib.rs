@@ -0,0 +1,62 @@
+use llvm_plugin::PassBuilder;
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    #[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+    builder.add_machine_function_pipeline_parsing_callback(|name, manager| {
+        if name == "mfpass" {
+            manager.add_pass(machine::MachineFunctionPass);
+            llvm_plugin::PipelineParsing::Parsed
+        } else {
+            llvm_plugin::PipelineParsing::NotParsed
+        }
+    });
+
+    #[cfg(not(any(feature = "llvm19-1", feature = "llvm20-1")))]
+    let _ = builder;
+}
+
+#[cfg(any(feature = "llvm19-1", feature = "llvm20-1"))]
+mod machine {
+    use llvm_plugin::{
+        LlvmMachineFunctionPass, MachineFunction, MachineOperand, PreservedAnalyses,
+    };
+
+    pub struct MachineFunctionPass;
+    impl LlvmMachineFunctionPass for MachineFunctionPass {
+        fn run_pass(&self, function: &mut MachineFunction) -> PreservedAnalyses {
+            assert_eq!(function.name(), function.function().get_name());
+
+            let blocks = function.basic_blocks();
+            let names: Vec<_> = blocks.iter().map(|block| block.name().to_bytes()).collect();
+            assert_eq!(names, [&b"entry"[..], b"exit"]);
+
+            for block in &blocks {
+                for instr in block.instructions() {
+                    assert_eq!(instr.parent(), *block);
+                    assert!(!instr.opcode_name().is_empty());
+
+                    for operand in instr.operands() {
+                        if let MachineOperand::BasicBlock(target) = operand {
+                            assert!(target.number() >= 0);
+                        }
+                    }
+                }
+            }
+
+            // the function returns, so at least one block ends with a terminator
+            assert!(blocks
+                .iter()
+                .flat_map(|block| block.instructions())
+                .any(|instr| instr.is_terminator()));
+
+            PreservedAnalyses::All
+        }
+
+        fn name(&self) -> &str {
+            "mfpass"
+        }
+
+        const REQUIRED: bool = true;
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    #[cfg(not(feature = "llvm11-0"))]
+    builder.add_loop_pipeline_parsing_callback(|name, manager| {
+        if name == "check-loop-nest" {
+            manager.add_loop_nest_pass(nest::CheckLoopNestPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "loop-nest-test" {
+            #[cfg(not(feature = "llvm11-0"))]
+            manager
+                .add_pipeline_text("function(loop(check-loop-nest))")
+                .unwrap();
+
+            manager.add_pass(CheckVisitedPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static NESTS_VISITED: AtomicU32 = AtomicU32::new(0);
+
+#[cfg(not(feature = "llvm11-0"))]
+mod nest {
+    use std::sync::atomic::Ordering;
+
+    use llvm_plugin::{LlvmLoopNestPass, LoopNest, PreservedAnalyses};
+
+    pub struct CheckLoopNestPass;
+    impl LlvmLoopNestPass for CheckLoopNestPass {
+        fn run_pass(&self, loop_nest: &mut LoopNest) -> PreservedAnalyses {
+            super::NESTS_VISITED.fetch_add(1, Ordering::SeqCst);
+
+            assert_eq!(loop_nest.function().get_name().to_bytes(), b"nest");
+            assert_eq!(loop_nest.depth(), 2);
+            assert_eq!(loop_nest.max_perfect_depth(), 2);
+            assert!(loop_nest.is_perfect());
+
+            let loops = loop_nest.loops();
+            assert_eq!(loops.len(), 2);
+            let (outer, inner) = (loops[0], loops[1]);
+            assert_eq!(loop_nest.outermost_loop(), outer);
+
+            assert_eq!(outer.depth(), 1);
+            assert_eq!(outer.header().get_name().to_bytes(), b"for.outer");
+            assert_eq!(outer.sub_loops(), [inner]);
+            assert!(!outer.is_innermost());
+
+            assert_eq!(inner.depth(), 2);
+            assert_eq!(inner.header().get_name().to_bytes(), b"for.inner");
+            assert_eq!(inner.blocks().len(), 2);
+            assert!(inner.is_innermost());
+            assert!(inner
+                .blocks()
+                .iter()
+                .all(|block| outer.blocks().contains(block)));
+
+            PreservedAnalyses::All
+        }
+    }
+}
+
+struct CheckVisitedPass;
+impl LlvmModulePass for CheckVisitedPass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        #[cfg(not(feature = "llvm11-0"))]
+        assert_eq!(NESTS_VISITED.load(Ordering::SeqCst), 1);
+
+        #[cfg(feature = "llvm11-0")]
+        assert_eq!(NESTS_VISITED.load(Ordering::SeqCst), 0);
+
+        PreservedAnalyses::All
+    }
+}
@@ -0,0 +1,37 @@
+use llvm_plugin::{
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "plugin27", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "crash" {
+            manager.add_pass(CrashPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+extern "C" {
+    // unlike `std::process::abort`, raises `SIGABRT` on every platform, which
+    // makes LLVM print its stack dump
+    fn abort() -> !;
+}
+
+struct CrashPass;
+impl LlvmFunctionPass for CrashPass {
+    fn run_pass(
+        &self,
+        _function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        unsafe { abort() }
+    }
+
+    fn name(&self) -> &str {
+        "plugin27-crash"
+    }
+}
@@ -1,9 +1,9 @@
 use llvm_plugin::inkwell::basic_block::BasicBlock;
-use llvm_plugin::inkwell::module::Module;
-use llvm_plugin::inkwell::values::{FunctionValue, InstructionOpcode, InstructionValue};
+use llvm_plugin::inkwell::values::{InstructionOpcode, InstructionValue};
 use llvm_plugin::{
-    AnalysisKey, FunctionAnalysisManager, LlvmFunctionAnalysis, LlvmFunctionPass, LlvmModulePass,
-    ModuleAnalysisManager, PassBuilder, PipelineParsing, PreservedAnalyses,
+    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis, LlvmFunctionPass,
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
 };
 
 #[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
@@ -33,10 +33,13 @@ fn plugin_registrar(builder: &mut PassBuilder) {
 
 struct Pass1;
 impl LlvmModulePass for Pass1 {
-    fn run_pass(&self, module: &mut Module, manager: &ModuleAnalysisManager) -> PreservedAnalyses {
-        let manager = manager
-            .get_function_analysis_manager_proxy(&module)
-            .get_manager();
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        let proxy = manager.get_function_analysis_manager_proxy(&module);
+        let manager = proxy.get_manager();
 
         for function in module.get_functions() {
             let result = manager.get_cached_result::<Ana1>(&function);
@@ -58,7 +61,7 @@ struct Pass2;
 impl LlvmFunctionPass for Pass2 {
     fn run_pass(
         &self,
-        function: &mut FunctionValue,
+        function: &mut FunctionRef,
         manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses {
         let result = manager.get_cached_result::<Ana1>(&function);
@@ -81,7 +84,7 @@ impl LlvmFunctionAnalysis for Ana1 {
 
     fn run_analysis(
         &self,
-        function: &FunctionValue,
+        function: &FunctionRef,
         _manager: &FunctionAnalysisManager,
     ) -> Self::Result {
         function
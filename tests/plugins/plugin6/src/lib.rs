@@ -1,8 +1,6 @@
-use llvm_plugin::inkwell::module::Module;
-use llvm_plugin::inkwell::values::FunctionValue;
 use llvm_plugin::{
-    FunctionAnalysisManager, LlvmFunctionPass, LlvmModulePass, ModuleAnalysisManager,
-    OptimizationLevel, PassBuilder, PreservedAnalyses,
+    FunctionAnalysisManager, FunctionRef, LlvmFunctionPass, LlvmModulePass, ModuleAnalysisManager,
+    ModuleRef, OptimizationLevel, PassBuilder, PreservedAnalyses,
 };
 
 #[cfg(feature = "llvm20-1")]
@@ -94,7 +92,7 @@ struct PeepholePass;
 impl LlvmFunctionPass for PeepholePass {
     fn run_pass(
         &self,
-        _function: &mut FunctionValue,
+        _function: &mut FunctionRef,
         _manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { PEEPHOLE_PASS_CALLED += 1 };
@@ -114,7 +112,7 @@ struct OptimizerLatePass;
 impl LlvmFunctionPass for OptimizerLatePass {
     fn run_pass(
         &self,
-        _function: &mut FunctionValue,
+        _function: &mut FunctionRef,
         _manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { OPT_LATE_PASS_CALLED += 1 };
@@ -134,7 +132,7 @@ struct VectorizerStartPass;
 impl LlvmFunctionPass for VectorizerStartPass {
     fn run_pass(
         &self,
-        _function: &mut FunctionValue,
+        _function: &mut FunctionRef,
         _manager: &FunctionAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { VEC_START_PASS_CALLED += 1 };
@@ -154,7 +152,7 @@ struct PipelineStartPass;
 impl LlvmModulePass for PipelineStartPass {
     fn run_pass(
         &self,
-        _module: &mut Module,
+        _module: &mut ModuleRef,
         _manager: &ModuleAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { PIPE_START_PASS_CALLED += 1 };
@@ -174,7 +172,7 @@ struct PipelineEarlySimpPass;
 impl LlvmModulePass for PipelineEarlySimpPass {
     fn run_pass(
         &self,
-        _module: &mut Module,
+        _module: &mut ModuleRef,
         _manager: &ModuleAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { PIPE_EARLY_PASS_CALLED += 1 };
@@ -194,7 +192,7 @@ struct OptimizerLastPass;
 impl LlvmModulePass for OptimizerLastPass {
     fn run_pass(
         &self,
-        _module: &mut Module,
+        _module: &mut ModuleRef,
         _manager: &ModuleAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { OPT_LAST_PASS_CALLED += 1 };
@@ -214,7 +212,7 @@ struct OptimizerEarlyPass;
 impl LlvmModulePass for OptimizerEarlyPass {
     fn run_pass(
         &self,
-        _module: &mut Module,
+        _module: &mut ModuleRef,
         _manager: &ModuleAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { OPT_EARLY_PASS_CALLED += 1 };
@@ -1,5 +1,6 @@
-use llvm_plugin::inkwell::module::Module;
-use llvm_plugin::{LlvmModulePass, ModuleAnalysisManager, PassBuilder, PreservedAnalyses};
+use llvm_plugin::{
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PreservedAnalyses,
+};
 
 #[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
 fn plugin_registrar(builder: &mut PassBuilder) {
@@ -38,7 +39,7 @@ struct FullLtoEarlyPass;
 impl LlvmModulePass for FullLtoEarlyPass {
     fn run_pass(
         &self,
-        _module: &mut Module,
+        _module: &mut ModuleRef,
         _manager: &ModuleAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { LTO_EARLY_PASS_CALLED += 1 };
@@ -58,7 +59,7 @@ struct FullLtoLastPass;
 impl LlvmModulePass for FullLtoLastPass {
     fn run_pass(
         &self,
-        _module: &mut Module,
+        _module: &mut ModuleRef,
         _manager: &ModuleAnalysisManager,
     ) -> PreservedAnalyses {
         unsafe { LTO_LAST_PASS_CALLED += 1 };
@@ -0,0 +1,110 @@
+use llvm_plugin::{
+    AnalysisKey, FunctionAnalysisManager, FunctionRef, LlvmFunctionAnalysis, LlvmFunctionPass,
+    LlvmModulePass, ModuleAnalysisManager, ModuleRef, PassBuilder, PipelineParsing,
+    PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "fpass" {
+            manager.add_pass(PanickingPass);
+            PipelineParsing::Parsed
+        } else if name == "check-invalidated" {
+            manager.add_pass(CheckInvalidatedPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_function_analysis_registration_callback(|manager| {
+        manager.register_pass(Analysis);
+    });
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_pass(ModulePass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static mut PANICKING_PASS_CALLED: u32 = 0;
+
+struct PanickingPass;
+impl LlvmFunctionPass for PanickingPass {
+    const ABORT_ON_PANIC: bool = false;
+
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef,
+        manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        unsafe { PANICKING_PASS_CALLED += 1 };
+        manager.get_result::<Analysis>(function);
+        panic!("expected panic");
+    }
+}
+
+static mut ANALYSIS_CALLED: u32 = 0;
+
+struct Analysis;
+impl LlvmFunctionAnalysis for Analysis {
+    type Result = ();
+
+    fn run_analysis(
+        &self,
+        _function: &FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> Self::Result {
+        unsafe { ANALYSIS_CALLED += 1 };
+    }
+
+    fn id() -> AnalysisKey {
+        static ID: u8 = 0;
+        &ID
+    }
+}
+
+struct CheckInvalidatedPass;
+impl LlvmFunctionPass for CheckInvalidatedPass {
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef,
+        manager: &FunctionAnalysisManager,
+    ) -> PreservedAnalyses {
+        // the panicking pass may have modified the function, so the result
+        // it computed must have been invalidated
+        assert!(manager.get_cached_result::<Analysis>(function).is_none());
+        manager.get_result::<Analysis>(function);
+        assert_eq!(
+            unsafe { ANALYSIS_CALLED },
+            2 * unsafe { PANICKING_PASS_CALLED }
+        );
+        PreservedAnalyses::All
+    }
+}
+
+static mut MODULE_PASS_CALLED: u32 = 0;
+
+struct ModulePass;
+impl LlvmModulePass for ModulePass {
+    fn run_pass(
+        &self,
+        _module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> PreservedAnalyses {
+        assert!(unsafe { PANICKING_PASS_CALLED } > 0);
+        unsafe { MODULE_PASS_CALLED += 1 };
+        PreservedAnalyses::All
+    }
+}
+
+impl Drop for ModulePass {
+    fn drop(&mut self) {
+        assert!(unsafe { MODULE_PASS_CALLED } > 0);
+    }
+}
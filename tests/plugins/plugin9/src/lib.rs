@@ -0,0 +1,129 @@
+use llvm_plugin::inkwell::values::InstructionOpcode;
+use llvm_plugin::{
+    DiagnosticLocation, DiagnosticSeverity, FunctionAnalysisManager, FunctionRef,
+    LlvmFallibleFunctionPass, LlvmFallibleModulePass, ModuleAnalysisManager, ModuleRef,
+    PassBuilder, PassError, PipelineParsing, PreservedAnalyses,
+};
+
+#[llvm_plugin::plugin(name = "llvm_plugin", version = "0.1")]
+fn plugin_registrar(builder: &mut PassBuilder) {
+    builder.add_function_pipeline_parsing_callback(|name, manager| {
+        if name == "fpass" {
+            manager.add_pass(FailingPass);
+            PipelineParsing::Parsed
+        } else if name == "lint" {
+            manager.add_pass(LintPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+
+    builder.add_module_pipeline_parsing_callback(|name, manager| {
+        if name == "mpass" {
+            manager.add_pass(ModulePass);
+            PipelineParsing::Parsed
+        } else if name == "module-lint" {
+            manager.add_pass(ModuleLintPass);
+            PipelineParsing::Parsed
+        } else {
+            PipelineParsing::NotParsed
+        }
+    });
+}
+
+static mut FAILING_PASS_CALLED: u32 = 0;
+
+struct FailingPass;
+impl LlvmFallibleFunctionPass for FailingPass {
+    fn run_pass(
+        &self,
+        _function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> Result<PreservedAnalyses, PassError> {
+        unsafe { FAILING_PASS_CALLED += 1 };
+        Err(PassError::new("expected error")
+            .with_severity(DiagnosticSeverity::Warning)
+            .with_location(DiagnosticLocation::new("test.c", 1, 1)))
+    }
+}
+
+static mut MODULE_PASS_CALLED: u32 = 0;
+
+struct ModulePass;
+impl LlvmFallibleModulePass for ModulePass {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> Result<PreservedAnalyses, PassError> {
+        assert!(unsafe { FAILING_PASS_CALLED } > 0);
+
+        let diagnostics = module.diagnostics();
+        for function in module.get_functions() {
+            for bb in function.get_basic_blocks() {
+                for inst in bb.get_instructions() {
+                    let location = DiagnosticLocation::from_instruction(&inst);
+                    diagnostics.emit(DiagnosticSeverity::Note, "visited", location.as_ref());
+                }
+            }
+        }
+
+        unsafe { MODULE_PASS_CALLED += 1 };
+        Ok(PreservedAnalyses::All)
+    }
+}
+
+impl Drop for ModulePass {
+    fn drop(&mut self) {
+        assert!(unsafe { MODULE_PASS_CALLED } > 0);
+    }
+}
+
+struct LintPass;
+impl LlvmFallibleFunctionPass for LintPass {
+    fn run_pass(
+        &self,
+        function: &mut FunctionRef,
+        _manager: &FunctionAnalysisManager,
+    ) -> Result<PreservedAnalyses, PassError> {
+        let diagnostics = function.diagnostics();
+        for bb in function.get_basic_blocks() {
+            for inst in bb.get_instructions() {
+                if inst.get_opcode() != InstructionOpcode::Add {
+                    continue;
+                }
+
+                let location = DiagnosticLocation::from_instruction(&inst).unwrap();
+                assert!(location.file().ends_with("lint.c"));
+                assert!(location.file().starts_with("/tmp/src"));
+                assert_eq!((location.line(), location.column()), (12, 3));
+                diagnostics.emit(
+                    DiagnosticSeverity::Warning,
+                    "suspicious add",
+                    Some(&location),
+                );
+            }
+        }
+        Ok(PreservedAnalyses::All)
+    }
+}
+
+struct ModuleLintPass;
+impl LlvmFallibleModulePass for ModuleLintPass {
+    fn run_pass(
+        &self,
+        module: &mut ModuleRef,
+        _manager: &ModuleAnalysisManager,
+    ) -> Result<PreservedAnalyses, PassError> {
+        let inst = module
+            .get_function("lint")
+            .and_then(|function| function.get_last_basic_block())
+            .and_then(|bb| bb.get_last_instruction())
+            .unwrap();
+        let location = DiagnosticLocation::from_instruction(&inst).unwrap();
+        Err(PassError::new("suspicious return")
+            .with_severity(DiagnosticSeverity::Warning)
+            .with_location(location))
+    }
+}